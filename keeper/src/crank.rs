@@ -0,0 +1,146 @@
+//! Builds and sends crank transactions.
+
+use std::thread::sleep;
+use std::time::Duration;
+
+use agentreputation_dao::state::{PostedTask, VouchRecord};
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+use anyhow::Result;
+use log::warn;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+
+/// Accounts shared by every `apply_decay_batch` call
+pub struct DecayBatchAccounts {
+    pub program_id: Pubkey,
+    pub keeper: Pubkey,
+    pub config: Pubkey,
+    pub tier_config: Pubkey,
+    pub treasury: Pubkey,
+    pub treasury_vault: Pubkey,
+    pub keeper_token_account: Pubkey,
+}
+
+impl DecayBatchAccounts {
+    pub fn instruction(&self, profiles: &[Pubkey]) -> Instruction {
+        let mut accounts = agentreputation_dao::accounts::ApplyDecayBatch {
+            keeper: self.keeper,
+            config: self.config,
+            tier_config: self.tier_config,
+            treasury: self.treasury,
+            treasury_vault: self.treasury_vault,
+            keeper_token_account: self.keeper_token_account,
+            token_program: anchor_spl::token::ID,
+        }
+        .to_account_metas(None);
+        accounts.extend(profiles.iter().map(|profile| AccountMeta::new(*profile, false)));
+
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: agentreputation_dao::instruction::ApplyDecayBatch {}.data(),
+        }
+    }
+}
+
+/// Accounts shared by every `expire_vouch` and `expire_task` call
+pub struct ExpiryAccounts {
+    pub program_id: Pubkey,
+    pub keeper: Pubkey,
+    pub config: Pubkey,
+    pub reputation_mint: Pubkey,
+    pub tier_config: Pubkey,
+}
+
+impl ExpiryAccounts {
+    pub fn expire_vouch(&self, vouch_record: Pubkey, vouch: &VouchRecord) -> Instruction {
+        let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &self.program_id).0;
+        let vouch_escrow = pda(&[b"escrow", vouch_record.as_ref()]);
+        let accounts = agentreputation_dao::accounts::ExpireVouch {
+            caller: self.keeper,
+            voucher: vouch.voucher,
+            voucher_profile: pda(&[b"agent", vouch.voucher.as_ref()]),
+            vouch_record,
+            vouched_for_profile: pda(&[b"agent", vouch.vouched_for.as_ref()]),
+            tier_config: self.tier_config,
+            config: self.config,
+            voucher_token_account: get_associated_token_address(&vouch.voucher, &self.reputation_mint),
+            escrow_token_account: vouch
+                .is_escrowed
+                .then(|| get_associated_token_address(&vouch_escrow, &self.reputation_mint)),
+            vouch_escrow,
+            reward_vault: pda(&[b"reward_vault"]),
+            mint_authority: pda(&[b"mint_authority"]),
+            token_program: anchor_spl::token::ID,
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: agentreputation_dao::instruction::ExpireVouch {}.data(),
+        }
+    }
+
+    pub fn expire_task(&self, task_address: Pubkey, task: &PostedTask) -> Instruction {
+        let pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &self.program_id).0;
+        let accounts = agentreputation_dao::accounts::ExpireTask {
+            caller: self.keeper,
+            requester: task.requester,
+            task: task_address,
+            escrow: task.escrow,
+            requester_token_account: get_associated_token_address(&task.requester, &self.reputation_mint),
+            agent_profile: pda(&[b"agent", task.agent.as_ref()]),
+            tier_config: self.tier_config,
+            task_record: pda(&[b"posted_task_record", task_address.as_ref()]),
+            config: self.config,
+            token_program: anchor_spl::token::ID,
+            system_program: solana_sdk::system_program::ID,
+        }
+        .to_account_metas(None);
+
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: agentreputation_dao::instruction::ExpireTask {}.data(),
+        }
+    }
+}
+
+/// Send `instruction`, retrying with a fresh blockhash and exponential
+/// backoff (1s, 2s, 4s, ...) up to `max_retries` times
+pub fn send_with_retries(
+    rpc: &RpcClient,
+    signer: &Keypair,
+    instruction: &Instruction,
+    max_retries: u32,
+) -> Result<Signature> {
+    let mut attempt = 0;
+    loop {
+        match send_once(rpc, signer, instruction) {
+            Ok(signature) => return Ok(signature),
+            Err(err) if attempt < max_retries => {
+                let backoff = Duration::from_secs(1 << attempt.min(6));
+                warn!("Crank failed (attempt {}): {err:#}. Retrying in {backoff:?}", attempt + 1);
+                sleep(backoff);
+                attempt += 1;
+            }
+            Err(err) => return Err(err.context(format!("crank failed after {} attempts", attempt + 1))),
+        }
+    }
+}
+
+fn send_once(rpc: &RpcClient, signer: &Keypair, instruction: &Instruction) -> Result<Signature> {
+    let blockhash = rpc.get_latest_blockhash()?;
+    let transaction = Transaction::new_signed_with_payer(
+        std::slice::from_ref(instruction),
+        Some(&signer.pubkey()),
+        &[signer],
+        blockhash,
+    );
+    Ok(rpc.send_and_confirm_transaction(&transaction)?)
+}
@@ -0,0 +1,259 @@
+//! Keeper bot for reputation decay and expiry.
+//!
+//! Scans every `AgentProfile` over RPC, works out which ones are due using the
+//! program's own decay rules, and cranks them with `apply_decay_batch`. It then
+//! closes expired vouches with `expire_vouch`, so they stop counting towards
+//! their target, and refunds overdue tasks with `expire_task`.
+//! `--dry-run` prints what each crank would do instead.
+
+mod crank;
+mod scan;
+
+use std::path::PathBuf;
+use std::thread::sleep;
+use std::time::Duration;
+
+use agentreputation_dao::state::{AgentProfile, ProtocolConfig, PAUSE_WITHDRAWALS};
+use anchor_spl::associated_token::get_associated_token_address;
+use anyhow::{anyhow, bail, Result};
+use clap::Parser;
+use log::{error, info, warn};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+
+use crate::crank::{send_with_retries, DecayBatchAccounts, ExpiryAccounts};
+use crate::scan::{
+    account_exists, cluster_time, decay_candidates, expired_vouches, fetch_config, fetch_posted_tasks, fetch_profiles,
+    fetch_treasury, fetch_vouches, overdue_tasks, DecayCandidate,
+};
+
+#[derive(Parser, Debug)]
+#[command(about = "Cranks reputation decay and expiry for agentreputation-dao")]
+struct Args {
+    /// RPC endpoint
+    #[arg(long, env = "RPC_URL", default_value = "http://127.0.0.1:8899")]
+    url: String,
+
+    /// Keypair that pays for cranks and receives the keeper reward
+    /// [default: ~/.config/solana/id.json]
+    #[arg(long, env = "KEEPER_KEYPAIR")]
+    keypair: Option<PathBuf>,
+
+    /// Reputation program to crank
+    #[arg(long, default_value_t = agentreputation_dao::ID)]
+    program_id: Pubkey,
+
+    /// Profiles per `apply_decay_batch` transaction
+    #[arg(long, default_value_t = 10)]
+    batch_size: usize,
+
+    /// Retries per transaction before the batch is given up on
+    #[arg(long, default_value_t = 3)]
+    max_retries: u32,
+
+    /// Print what would be decayed without sending anything
+    #[arg(long)]
+    dry_run: bool,
+
+    /// Keep running, rescanning every this many seconds
+    #[arg(long)]
+    interval: Option<u64>,
+}
+
+fn main() -> Result<()> {
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+    let args = Args::parse();
+    if args.batch_size == 0 {
+        bail!("--batch-size must be at least 1");
+    }
+
+    let rpc = RpcClient::new_with_commitment(args.url.clone(), CommitmentConfig::confirmed());
+    // A dry run never signs, so it doesn't need a keypair
+    let keeper = if args.dry_run { None } else { Some(load_keypair(args.keypair.clone())?) };
+
+    match args.interval {
+        None => run_once(&rpc, keeper.as_ref(), &args),
+        Some(interval) => loop {
+            // A bad round shouldn't stop a long-running keeper
+            if let Err(err) = run_once(&rpc, keeper.as_ref(), &args) {
+                error!("Round failed: {err:#}");
+            }
+            sleep(Duration::from_secs(interval));
+        },
+    }
+}
+
+fn load_keypair(path: Option<PathBuf>) -> Result<Keypair> {
+    let path = match path {
+        Some(path) => path,
+        None => {
+            let home = std::env::var("HOME").map_err(|_| anyhow!("HOME is not set; pass --keypair"))?;
+            PathBuf::from(home).join(".config/solana/id.json")
+        }
+    };
+    read_keypair_file(&path).map_err(|err| anyhow!("reading keypair {}: {err}", path.display()))
+}
+
+fn run_once(rpc: &RpcClient, keeper: Option<&Keypair>, args: &Args) -> Result<()> {
+    let program_id = args.program_id;
+    let config = fetch_config(rpc, &program_id)?;
+    let now = cluster_time(rpc)?;
+    let profiles = fetch_profiles(rpc, &program_id)?;
+
+    let mut failed = crank_decay(rpc, keeper, args, &config, &profiles, now)?;
+    if config.paused & PAUSE_WITHDRAWALS != 0 {
+        // Both expiry instructions move escrowed tokens
+        info!("Withdrawals are paused; skipping expired vouches and overdue tasks");
+    } else {
+        failed += crank_expiry(rpc, keeper, args, &config, &profiles, now)?;
+    }
+
+    if failed > 0 {
+        bail!("{failed} cranks failed");
+    }
+    Ok(())
+}
+
+/// Decay every profile that's due. Returns how many profiles weren't decayed.
+fn crank_decay(
+    rpc: &RpcClient,
+    keeper: Option<&Keypair>,
+    args: &Args,
+    config: &ProtocolConfig,
+    profiles: &[(Pubkey, AgentProfile)],
+    now: i64,
+) -> Result<usize> {
+    let program_id = args.program_id;
+    let candidates = decay_candidates(profiles, config, now);
+    info!("Scanned {} profiles, {} due for decay", profiles.len(), candidates.len());
+
+    let Some(keeper) = keeper else {
+        for candidate in &candidates {
+            print_candidate(candidate);
+        }
+        let reward = (candidates.len() as u64).saturating_mul(config.decay_keeper_reward);
+        info!("Dry run: would earn up to {reward} in keeper rewards");
+        return Ok(0);
+    };
+    if candidates.is_empty() {
+        return Ok(0);
+    }
+
+    let (treasury, treasury_state) = fetch_treasury(rpc, &program_id)?;
+    let keeper_token_account = get_associated_token_address(&keeper.pubkey(), &config.reputation_mint);
+    if !account_exists(rpc, &keeper_token_account)? {
+        bail!("keeper token account {keeper_token_account} doesn't exist; create the reputation mint ATA first");
+    }
+    let accounts = DecayBatchAccounts {
+        program_id,
+        keeper: keeper.pubkey(),
+        config: Pubkey::find_program_address(&[b"config"], &program_id).0,
+        tier_config: Pubkey::find_program_address(&[b"tier_config"], &program_id).0,
+        treasury,
+        treasury_vault: treasury_state.vault,
+        keeper_token_account,
+    };
+
+    let mut failed = 0;
+    for batch in candidates.chunks(args.batch_size) {
+        let profiles: Vec<Pubkey> = batch.iter().map(|c| c.profile).collect();
+        match send_with_retries(rpc, keeper, &accounts.instruction(&profiles), args.max_retries) {
+            Ok(signature) => {
+                info!("Decayed {} profiles: {signature}", batch.len());
+                batch.iter().for_each(print_candidate);
+            }
+            Err(err) => {
+                error!("Batch of {} profiles failed: {err:#}", batch.len());
+                failed += batch.len();
+            }
+        }
+    }
+
+    if failed > 0 {
+        error!("{failed} of {} profiles were not decayed", candidates.len());
+    }
+    Ok(failed)
+}
+
+/// Close expired vouches and refund overdue tasks, one transaction each.
+/// Returns how many cranks failed.
+fn crank_expiry(
+    rpc: &RpcClient,
+    keeper: Option<&Keypair>,
+    args: &Args,
+    config: &ProtocolConfig,
+    profiles: &[(Pubkey, AgentProfile)],
+    now: i64,
+) -> Result<usize> {
+    let program_id = args.program_id;
+    let vouches = fetch_vouches(rpc, &program_id)?;
+    let expired = expired_vouches(&vouches, profiles, now);
+    let tasks = fetch_posted_tasks(rpc, &program_id)?;
+    let overdue = overdue_tasks(&tasks, now);
+    info!(
+        "Scanned {} vouches and {} tasks: {} expired, {} overdue",
+        vouches.len(), tasks.len(), expired.len(), overdue.len()
+    );
+
+    let Some(keeper) = keeper else {
+        for (address, vouch) in &expired {
+            println!("vouch {address}: {} -> {} expired at {}", vouch.voucher, vouch.vouched_for, vouch.expires_at);
+        }
+        for (address, task) in &overdue {
+            println!("task {address} (agent {}): deadline {}", task.agent, task.deadline);
+        }
+        return Ok(0);
+    };
+    if expired.is_empty() && overdue.is_empty() {
+        return Ok(0);
+    }
+
+    let accounts = ExpiryAccounts {
+        program_id,
+        keeper: keeper.pubkey(),
+        config: Pubkey::find_program_address(&[b"config"], &program_id).0,
+        reputation_mint: config.reputation_mint,
+        tier_config: Pubkey::find_program_address(&[b"tier_config"], &program_id).0,
+    };
+
+    let mut failed = 0;
+    for (address, vouch) in expired {
+        // The refund goes to the voucher's ATA, which only they can create
+        let refund_account = get_associated_token_address(&vouch.voucher, &config.reputation_mint);
+        if !account_exists(rpc, &refund_account)? {
+            warn!("Skipping vouch {address}: voucher {} has no reputation token account", vouch.voucher);
+            continue;
+        }
+        match send_with_retries(rpc, keeper, &accounts.expire_vouch(*address, vouch), args.max_retries) {
+            Ok(signature) => info!("Expired vouch {address}: {signature}"),
+            Err(err) => {
+                error!("Expiring vouch {address} failed: {err:#}");
+                failed += 1;
+            }
+        }
+    }
+    for (address, task) in overdue {
+        let refund_account = get_associated_token_address(&task.requester, &config.reputation_mint);
+        if !account_exists(rpc, &refund_account)? {
+            warn!("Skipping task {address}: requester {} has no reputation token account", task.requester);
+            continue;
+        }
+        match send_with_retries(rpc, keeper, &accounts.expire_task(*address, task), args.max_retries) {
+            Ok(signature) => info!("Expired task {address}: {signature}"),
+            Err(err) => {
+                error!("Expiring task {address} failed: {err:#}");
+                failed += 1;
+            }
+        }
+    }
+    Ok(failed)
+}
+
+fn print_candidate(candidate: &DecayCandidate) {
+    println!(
+        "{} (agent {}): {} -> {} ({} days)",
+        candidate.profile, candidate.agent, candidate.old_score, candidate.new_score, candidate.days
+    );
+}
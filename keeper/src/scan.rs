@@ -0,0 +1,270 @@
+//! Reads program state over RPC and works out which profiles are due for
+//! decay, which vouches have expired and which tasks are overdue.
+
+use std::collections::HashMap;
+
+use agentreputation_dao::state::{
+    calculate_decay_days, calculate_profile_decay, AgentProfile, PostedTask, ProtocolConfig, TaskStatus, Treasury,
+    VouchRecord,
+};
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::{Context, Result};
+use log::warn;
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_sdk::account::from_account;
+use solana_sdk::clock::Clock;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::sysvar;
+
+/// A profile `apply_decay_batch` would decay, with the change it would make
+#[derive(Clone, Debug)]
+pub struct DecayCandidate {
+    pub profile: Pubkey,
+    pub agent: Pubkey,
+    pub old_score: u64,
+    pub new_score: u64,
+    pub days: i64,
+}
+
+pub fn fetch_account<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    let data = rpc
+        .get_account_data(address)
+        .with_context(|| format!("fetching account {address}"))?;
+    T::try_deserialize(&mut data.as_slice()).with_context(|| format!("decoding account {address}"))
+}
+
+pub fn fetch_config(rpc: &RpcClient, program_id: &Pubkey) -> Result<ProtocolConfig> {
+    let (config, _) = Pubkey::find_program_address(&[b"config"], program_id);
+    fetch_account(rpc, &config)
+}
+
+pub fn fetch_treasury(rpc: &RpcClient, program_id: &Pubkey) -> Result<(Pubkey, Treasury)> {
+    let (treasury, _) = Pubkey::find_program_address(&[b"treasury"], program_id);
+    Ok((treasury, fetch_account(rpc, &treasury)?))
+}
+
+pub fn account_exists(rpc: &RpcClient, address: &Pubkey) -> Result<bool> {
+    Ok(rpc.get_account_with_commitment(address, rpc.commitment())?.value.is_some())
+}
+
+/// Cluster time, so eligibility matches what the program will see
+pub fn cluster_time(rpc: &RpcClient) -> Result<i64> {
+    let account = rpc.get_account(&sysvar::clock::ID)?;
+    let clock: Clock = from_account(&account).context("decoding clock sysvar")?;
+    Ok(clock.unix_timestamp)
+}
+
+/// Every `AgentProfile` owned by the program. Accounts that don't decode
+/// (e.g. an older layout) are logged and skipped.
+pub fn fetch_profiles(rpc: &RpcClient, program_id: &Pubkey) -> Result<Vec<(Pubkey, AgentProfile)>> {
+    fetch_all(rpc, program_id, "agent profile")
+}
+
+/// Every `VouchRecord` owned by the program
+pub fn fetch_vouches(rpc: &RpcClient, program_id: &Pubkey) -> Result<Vec<(Pubkey, VouchRecord)>> {
+    fetch_all(rpc, program_id, "vouch record")
+}
+
+/// Every `PostedTask` owned by the program
+pub fn fetch_posted_tasks(rpc: &RpcClient, program_id: &Pubkey) -> Result<Vec<(Pubkey, PostedTask)>> {
+    fetch_all(rpc, program_id, "posted task")
+}
+
+/// Every program account of type `T`, found by its discriminator
+fn fetch_all<T: AccountDeserialize + Discriminator>(
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    kind: &str,
+) -> Result<Vec<(Pubkey, T)>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, &T::DISCRIMINATOR))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            commitment: Some(rpc.commitment()),
+            ..RpcAccountInfoConfig::default()
+        },
+        ..RpcProgramAccountsConfig::default()
+    };
+
+    let accounts = rpc
+        .get_program_accounts_with_config(program_id, config)
+        .with_context(|| format!("scanning {kind}s"))?;
+
+    Ok(accounts
+        .into_iter()
+        .filter_map(|(address, account)| {
+            match T::try_deserialize(&mut account.data.as_slice()) {
+                Ok(decoded) => Some((address, decoded)),
+                Err(err) => {
+                    warn!("Skipping {kind} {address}: {err}");
+                    None
+                }
+            }
+        })
+        .collect())
+}
+
+/// Profiles whose score would drop if decayed at `now`, using the same
+/// rules as the program: whole days past the grace period, down to the floor
+pub fn decay_candidates(profiles: &[(Pubkey, AgentProfile)], config: &ProtocolConfig, now: i64) -> Vec<DecayCandidate> {
+    profiles
+        .iter()
+        .filter_map(|(address, profile)| {
+            let days = calculate_decay_days(profile.last_activity_timestamp, now, config.decay_grace_days);
+            if days <= 0 {
+                return None;
+            }
+            let new_score =
+                calculate_profile_decay(profile.reputation_score, days, profile.total_tasks_completed, config);
+            (new_score < profile.reputation_score).then_some(DecayCandidate {
+                profile: *address,
+                agent: profile.owner,
+                old_score: profile.reputation_score,
+                new_score,
+                days,
+            })
+        })
+        .collect()
+}
+
+/// Vouches `expire_vouch` would close at `now`. Vouches for an agent with an
+/// open dispute are left alone, since the program refuses to expire them.
+pub fn expired_vouches<'a>(
+    vouches: &'a [(Pubkey, VouchRecord)],
+    profiles: &[(Pubkey, AgentProfile)],
+    now: i64,
+) -> Vec<&'a (Pubkey, VouchRecord)> {
+    let disputed: HashMap<Pubkey, bool> =
+        profiles.iter().map(|(_, profile)| (profile.owner, profile.open_disputes > 0)).collect();
+    vouches
+        .iter()
+        .filter(|(_, vouch)| vouch.is_expired(now))
+        .filter(|(_, vouch)| !disputed.get(&vouch.vouched_for).copied().unwrap_or(false))
+        .collect()
+}
+
+/// Accepted tasks past their deadline, which `expire_task` would refund
+pub fn overdue_tasks(tasks: &[(Pubkey, PostedTask)], now: i64) -> Vec<&(Pubkey, PostedTask)> {
+    tasks
+        .iter()
+        .filter(|(_, task)| task.status == TaskStatus::Accepted && now > task.deadline)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 86400;
+
+    fn config() -> ProtocolConfig {
+        ProtocolConfig {
+            decay_rate_per_day: 1000,
+            decay_grace_days: 2,
+            reputation_floor: 100,
+            max_reputation_floor: 100,
+            ..Default::default()
+        }
+    }
+
+    fn profile(score: u64, last_activity: i64) -> (Pubkey, AgentProfile) {
+        let profile = AgentProfile {
+            owner: Pubkey::new_unique(),
+            reputation_score: score,
+            last_activity_timestamp: last_activity,
+            is_active: true,
+            ..Default::default()
+        };
+        (Pubkey::new_unique(), profile)
+    }
+
+    fn vouch(vouched_for: Pubkey, expires_at: i64) -> (Pubkey, VouchRecord) {
+        let vouch = VouchRecord {
+            voucher: Pubkey::new_unique(),
+            vouched_for,
+            amount: 100,
+            is_positive: true,
+            is_escrowed: true,
+            expires_at,
+            ..Default::default()
+        };
+        (Pubkey::new_unique(), vouch)
+    }
+
+    fn task(status: TaskStatus, deadline: i64) -> (Pubkey, PostedTask) {
+        let task = PostedTask {
+            requester: Pubkey::new_unique(),
+            agent: Pubkey::new_unique(),
+            task_id: String::from("task"),
+            category_id: 0,
+            bounty: 0,
+            reputation_amount: 0,
+            escrow: Pubkey::new_unique(),
+            result_hash: [0; 32],
+            status,
+            posted_at: 0,
+            accepted_at: 0,
+            submitted_at: 0,
+            deadline,
+            bump: 0,
+        };
+        (Pubkey::new_unique(), task)
+    }
+
+    #[test]
+    fn decay_waits_out_the_grace_period() {
+        let profiles = [profile(1000, 0)];
+        assert!(decay_candidates(&profiles, &config(), 2 * DAY).is_empty());
+
+        let candidates = decay_candidates(&profiles, &config(), 3 * DAY);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].profile, profiles[0].0);
+        assert_eq!(candidates[0].agent, profiles[0].1.owner);
+        assert_eq!((candidates[0].old_score, candidates[0].new_score, candidates[0].days), (1000, 900, 1));
+    }
+
+    #[test]
+    fn decay_skips_profiles_at_their_floor() {
+        let profiles = [profile(100, 0), profile(50, 0), profile(105, 0)];
+        let candidates = decay_candidates(&profiles, &config(), 10 * DAY);
+
+        // Only the one above the floor drops, and only as far as the floor
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].profile, profiles[2].0);
+        assert_eq!(candidates[0].new_score, 100);
+    }
+
+    #[test]
+    fn expired_vouches_skip_open_ended_and_disputed_targets() {
+        let (_, calm) = profile(0, 0);
+        let (_, mut disputed) = profile(0, 0);
+        disputed.open_disputes = 1;
+        let vouches = [
+            vouch(calm.owner, DAY),
+            vouch(calm.owner, 2 * DAY),
+            vouch(calm.owner, 0),
+            vouch(disputed.owner, DAY),
+        ];
+        let profiles = [(Pubkey::new_unique(), calm), (Pubkey::new_unique(), disputed)];
+
+        let expired = expired_vouches(&vouches, &profiles, DAY);
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].0, vouches[0].0);
+    }
+
+    #[test]
+    fn overdue_tasks_are_accepted_and_past_deadline() {
+        let tasks = [
+            task(TaskStatus::Accepted, DAY),
+            task(TaskStatus::Accepted, DAY - 1),
+            task(TaskStatus::Submitted, 0),
+            task(TaskStatus::Open, 0),
+        ];
+        let overdue = overdue_tasks(&tasks, DAY);
+        assert_eq!(overdue.len(), 1);
+        assert_eq!(overdue[0].0, tasks[1].0);
+    }
+}
@@ -34,4 +34,210 @@ pub enum ReputationError {
     
     #[msg("Math overflow")]
     MathOverflow,
+    
+    #[msg("Signer is not the protocol authority")]
+    Unauthorized,
+    
+    #[msg("Snapshot inclusion proof is invalid")]
+    InvalidSnapshotProof,
+    
+    #[msg("Fee must be at most 10000 basis points")]
+    InvalidFee,
+    
+    #[msg("Treasury balance too low")]
+    InsufficientTreasuryBalance,
+    
+    #[msg("Vouch has no escrowed stake")]
+    NoEscrowedStake,
+    
+    #[msg("Minimum registration tenure not reached")]
+    TenureNotReached,
+    
+    #[msg("Agent still has stake locked in vouches")]
+    StakeStillLocked,
+    
+    #[msg("No vouch rewards to claim")]
+    NoRewardsToClaim,
+    
+    #[msg("Invalid vouch amount")]
+    InvalidVouchAmount,
+    
+    #[msg("Vouch record does not belong to this agent")]
+    InvalidVouchRecord,
+    
+    #[msg("Vouch expiry outside configured bounds")]
+    InvalidVouchExpiry,
+    
+    #[msg("Vouch has expired")]
+    VouchExpired,
+    
+    #[msg("Vouch has not expired yet")]
+    VouchNotExpired,
+    
+    #[msg("Category name too long")]
+    CategoryNameTooLong,
+    
+    #[msg("Skill category is not active")]
+    CategoryInactive,
+    
+    #[msg("Domain reputation account does not belong to this agent")]
+    InvalidDomainAccount,
+    
+    #[msg("Task is not in the required state")]
+    InvalidTaskStatus,
+    
+    #[msg("Invalid task bounty")]
+    InvalidBounty,
+    
+    #[msg("Dispute has not been resolved")]
+    DisputeNotResolved,
+    
+    #[msg("Dispute has already been resolved")]
+    DisputeAlreadyResolved,
+    
+    #[msg("Rating must be between 1 and 5")]
+    InvalidRating,
+    
+    #[msg("Task has already been rated")]
+    TaskAlreadyRated,
+    
+    #[msg("Invalid or missing task receipt")]
+    InvalidReceipt,
+    
+    #[msg("Client key is not active")]
+    ClientKeyInactive,
+    
+    #[msg("Task receipt has expired")]
+    ReceiptExpired,
+    
+    #[msg("Task deadline must be in the future")]
+    InvalidDeadline,
+    
+    #[msg("Task deadline has passed")]
+    DeadlinePassed,
+    
+    #[msg("Task deadline has not passed yet")]
+    DeadlineNotPassed,
+    
+    #[msg("Global reputation mint cap for this epoch exceeded")]
+    GlobalMintCapExceeded,
+    
+    #[msg("Agent reputation mint cap for this epoch exceeded")]
+    AgentMintCapExceeded,
+    
+    #[msg("Invalid mint cap configuration")]
+    InvalidMintCap,
+    
+    #[msg("This instruction group is paused")]
+    ProtocolPaused,
+    
+    #[msg("Invalid pause groups")]
+    InvalidPauseGroups,
+    
+    #[msg("Timelock queue is full")]
+    TimelockQueueFull,
+    
+    #[msg("Proposal is not in the timelock queue")]
+    ProposalNotQueued,
+    
+    #[msg("Proposal is not in the required state")]
+    InvalidProposalStatus,
+    
+    #[msg("Voting period has ended")]
+    VotingClosed,
+    
+    #[msg("Voting period has not ended yet")]
+    VotingNotEnded,
+    
+    #[msg("Timelock delay has not passed yet")]
+    TimelockNotExpired,
+    
+    #[msg("No voting power")]
+    NoVotingPower,
+    
+    #[msg("Invalid governance parameters")]
+    InvalidGovernanceParams,
+    
+    #[msg("Invalid vote delegation")]
+    InvalidDelegation,
+    
+    #[msg("Delegate has delegated its own votes")]
+    DelegationChain,
+    
+    #[msg("Already voted on this proposal")]
+    AlreadyVoted,
+    
+    #[msg("Juror pool is full")]
+    JurorPoolFull,
+    
+    #[msg("Not enough eligible jurors")]
+    NotEnoughJurors,
+    
+    #[msg("Not a juror on this jury")]
+    NotAJuror,
+    
+    #[msg("Outside the commit period")]
+    CommitPeriodClosed,
+    
+    #[msg("Outside the reveal period")]
+    RevealPeriodClosed,
+    
+    #[msg("Vote already committed")]
+    AlreadyCommitted,
+    
+    #[msg("Revealed vote does not match the commitment")]
+    InvalidReveal,
+    
+    #[msg("Jury cannot be finalized yet")]
+    JuryNotReady,
+    
+    #[msg("Juror exit delay has not passed")]
+    JurorExitPending,
+    
+    #[msg("Invalid jury parameters")]
+    InvalidJuryParams,
+    #[msg("Appeal window has closed")]
+    AppealWindowClosed,
+    #[msg("Verdict can still be appealed")]
+    AppealWindowOpen,
+    #[msg("No further appeals are possible")]
+    AppealLimitReached,
+    #[msg("Appeal bond has not been settled")]
+    AppealBondUnsettled,
+    #[msg("No appeal bond to settle")]
+    NoPendingAppeal,
+    #[msg("Agent has an open dispute")]
+    AgentUnderDispute,
+    #[msg("Tier thresholds must be ascending and at most 8")]
+    InvalidTierThresholds,
+    #[msg("Score history doesn't reach back that far")]
+    HistoryUnavailable,
+    #[msg("Invalid agent profile account")]
+    InvalidAgentProfile,
+    #[msg("Invalid decay grace period or reputation floor")]
+    InvalidDecayPolicy,
+    #[msg("Registration bond has not been slashed")]
+    BondNotSlashed,
+    #[msg("Task record is not at its expected address")]
+    InvalidTaskRecord,
+    #[msg("Jury can still be summoned")]
+    SummonPeriodOpen,
+    #[msg("Jury was not summoned in time")]
+    SummonPeriodClosed,
+    #[msg("A jury has already been summoned")]
+    JuryAlreadySummoned,
+    #[msg("Dispute bond has not been settled")]
+    DisputeBondUnsettled,
+    #[msg("No dispute bond to settle")]
+    NoDisputeBond,
+    #[msg("Jury seed slot has not been reached")]
+    SeedSlotNotReached,
+    #[msg("Jury seed slot is no longer in SlotHashes")]
+    SeedSlotExpired,
+    #[msg("Juror is seated on a jury that hasn't been finalized")]
+    JurorOnActiveCase,
+    #[msg("Agent still has vouches for or against it")]
+    VouchesOutstanding,
+    #[msg("Decay keeper reward is above the maximum")]
+    InvalidKeeperReward,
 }
@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+/// Emitted whenever an agent's cached tier moves up or down
+#[event]
+pub struct TierChanged {
+    pub agent: Pubkey,
+    pub old_tier: u8,
+    pub new_tier: u8,
+    pub reputation_score: u64,
+    pub timestamp: i64,
+}
@@ -0,0 +1,512 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount, Transfer};
+use crate::state::{AgentProfile, VouchRecord, ProtocolConfig, Treasury, calculate_fee, calculate_accrued_reward, calculate_pending_reward, PAUSE_VOUCHING, PAUSE_WITHDRAWALS};
+use crate::errors::ReputationError;
+use super::tier::{load_tier_config, update_tier};
+use crate::instructions::rewards::pay_vouch_reward;
+
+#[derive(Accounts)]
+pub struct IncreaseVouch<'info> {
+    #[account(mut)]
+    pub voucher: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"agent", voucher.key().as_ref()],
+        bump = voucher_profile.bump,
+    )]
+    pub voucher_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"vouch", voucher.key().as_ref(), vouch_record.vouched_for.as_ref()],
+        bump = vouch_record.bump,
+        constraint = vouch_record.is_positive @ ReputationError::NoEscrowedStake,
+    )]
+    pub vouch_record: Account<'info, VouchRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"agent", vouch_record.vouched_for.as_ref()],
+        bump = vouched_for_profile.bump,
+    )]
+    pub vouched_for_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        associated_token::mint = config.reputation_mint,
+        associated_token::authority = voucher,
+    )]
+    pub voucher_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = config.reputation_mint,
+        associated_token::authority = vouch_escrow,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns the escrow account
+    #[account(
+        seeds = [b"escrow", vouch_record.key().as_ref()],
+        bump,
+    )]
+    pub vouch_escrow: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        address = treasury.vault,
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault"],
+        bump,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: This is the token mint authority PDA
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, token::Token>,
+}
+
+pub fn increase_vouch(ctx: Context<IncreaseVouch>, amount: u64) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_VOUCHING)?;
+
+    require!(amount > 0, ReputationError::InvalidVouchAmount);
+    require!(ctx.accounts.voucher_profile.is_active, ReputationError::AgentInactive);
+
+    let clock = Clock::get()?;
+    require!(
+        !ctx.accounts.vouch_record.is_expired(clock.unix_timestamp),
+        ReputationError::VouchExpired
+    );
+    let reward_per_share = ctx.accounts.vouched_for_profile.reward_per_share;
+
+    // Settle rewards at the old stake before it changes
+    let pending = calculate_pending_reward(
+        ctx.accounts.vouch_record.amount,
+        reward_per_share,
+        ctx.accounts.vouch_record.reward_debt,
+    );
+    pay_vouch_reward(
+        &ctx.accounts.token_program,
+        &ctx.accounts.reward_vault,
+        &ctx.accounts.voucher_token_account,
+        &ctx.accounts.mint_authority,
+        ctx.bumps.mint_authority,
+        pending,
+    )?;
+
+    let fee = calculate_fee(amount, ctx.accounts.config.vouch_fee_bps);
+    let stake = amount.checked_sub(fee).ok_or(ReputationError::MathOverflow)?;
+
+    // Only the top-up gets a fresh lockup
+    let vouch = &mut ctx.accounts.vouch_record;
+    vouch.add_tranche(stake, clock.unix_timestamp);
+    vouch.reward_debt = calculate_accrued_reward(vouch.amount, reward_per_share);
+
+    ctx.accounts.voucher_profile.staked_amount =
+        ctx.accounts.voucher_profile.staked_amount.saturating_add(stake);
+    ctx.accounts.vouched_for_profile.total_vouch_stake =
+        ctx.accounts.vouched_for_profile.total_vouch_stake.saturating_add(stake);
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.voucher_token_account.to_account_info(),
+                to: ctx.accounts.escrow_token_account.to_account_info(),
+                authority: ctx.accounts.voucher.to_account_info(),
+            },
+        ),
+        stake,
+    )?;
+
+    if fee > 0 {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.voucher_token_account.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: ctx.accounts.voucher.to_account_info(),
+                },
+            ),
+            fee,
+        )?;
+        ctx.accounts.treasury.total_token_fees =
+            ctx.accounts.treasury.total_token_fees.saturating_add(fee);
+    }
+
+    msg!("Vouch increased by {} (fee {}) | New stake: {}",
+        stake, fee, ctx.accounts.vouch_record.amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DecreaseVouch<'info> {
+    #[account(mut)]
+    pub voucher: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"agent", voucher.key().as_ref()],
+        bump = voucher_profile.bump,
+    )]
+    pub voucher_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"vouch", voucher.key().as_ref(), vouch_record.vouched_for.as_ref()],
+        bump = vouch_record.bump,
+        constraint = vouch_record.is_positive @ ReputationError::NoEscrowedStake,
+    )]
+    pub vouch_record: Account<'info, VouchRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"agent", vouch_record.vouched_for.as_ref()],
+        bump = vouched_for_profile.bump,
+    )]
+    pub vouched_for_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        associated_token::mint = config.reputation_mint,
+        associated_token::authority = voucher,
+    )]
+    pub voucher_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = config.reputation_mint,
+        associated_token::authority = vouch_escrow,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns the escrow account
+    #[account(
+        seeds = [b"escrow", vouch_record.key().as_ref()],
+        bump,
+    )]
+    pub vouch_escrow: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault"],
+        bump,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: This is the token mint authority PDA
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, token::Token>,
+}
+
+pub fn decrease_vouch(ctx: Context<DecreaseVouch>, amount: u64) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_WITHDRAWALS)?;
+    require!(
+        ctx.accounts.vouched_for_profile.open_disputes == 0,
+        ReputationError::AgentUnderDispute
+    );
+
+    let clock = Clock::get()?;
+    let lockup = ctx.accounts.config.vouch_lockup_period;
+    let vouch = &ctx.accounts.vouch_record;
+
+    // Closing the vouch entirely goes through withdraw_vouch
+    require!(amount > 0 && amount < vouch.amount, ReputationError::InvalidVouchAmount);
+    require!(
+        amount <= vouch.unlocked_amount(clock.unix_timestamp, lockup),
+        ReputationError::LockupNotExpired
+    );
+
+    let reward_per_share = ctx.accounts.vouched_for_profile.reward_per_share;
+    let pending = calculate_pending_reward(vouch.amount, reward_per_share, vouch.reward_debt);
+    pay_vouch_reward(
+        &ctx.accounts.token_program,
+        &ctx.accounts.reward_vault,
+        &ctx.accounts.voucher_token_account,
+        &ctx.accounts.mint_authority,
+        ctx.bumps.mint_authority,
+        pending,
+    )?;
+
+    let vouch = &mut ctx.accounts.vouch_record;
+    vouch.remove_unlocked(amount, clock.unix_timestamp, lockup);
+    vouch.reward_debt = calculate_accrued_reward(vouch.amount, reward_per_share);
+
+    ctx.accounts.voucher_profile.staked_amount =
+        ctx.accounts.voucher_profile.staked_amount.saturating_sub(amount);
+    ctx.accounts.vouched_for_profile.total_vouch_stake =
+        ctx.accounts.vouched_for_profile.total_vouch_stake.saturating_sub(amount);
+
+    let vouch_key = ctx.accounts.vouch_record.key();
+    let seeds = &[b"escrow", vouch_key.as_ref(), &[ctx.bumps.vouch_escrow]];
+    let signer = &[&seeds[..]];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                to: ctx.accounts.voucher_token_account.to_account_info(),
+                authority: ctx.accounts.vouch_escrow.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+    )?;
+
+    msg!("Vouch decreased by {} | Remaining stake: {}",
+        amount, ctx.accounts.vouch_record.amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ReviseVouch<'info> {
+    #[account(mut)]
+    pub voucher: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"agent", voucher.key().as_ref()],
+        bump = voucher_profile.bump,
+    )]
+    pub voucher_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"vouch", voucher.key().as_ref(), vouch_record.vouched_for.as_ref()],
+        bump = vouch_record.bump,
+    )]
+    pub vouch_record: Account<'info, VouchRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"agent", vouch_record.vouched_for.as_ref()],
+        bump = vouched_for_profile.bump,
+    )]
+    pub vouched_for_profile: Account<'info, AgentProfile>,
+
+    /// CHECK: Tier config PDA, read by `load_tier_config`
+    #[account(
+        seeds = [b"tier_config"],
+        bump,
+    )]
+    pub tier_config: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        associated_token::mint = config.reputation_mint,
+        associated_token::authority = voucher,
+    )]
+    pub voucher_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        associated_token::mint = config.reputation_mint,
+        associated_token::authority = vouch_escrow,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA that owns the escrow account
+    #[account(
+        seeds = [b"escrow", vouch_record.key().as_ref()],
+        bump,
+    )]
+    pub vouch_escrow: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        address = treasury.vault,
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault"],
+        bump,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: This is the token mint authority PDA
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, token::Token>,
+}
+
+pub fn revise_vouch(ctx: Context<ReviseVouch>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_VOUCHING)?;
+
+    require!(ctx.accounts.voucher_profile.is_active, ReputationError::AgentInactive);
+    require!(
+        ctx.accounts.voucher_profile.reputation_score >= ctx.accounts.config.min_reputation_for_vouching,
+        ReputationError::InsufficientReputation
+    );
+    // Flipping sides mid-dispute would dodge whichever slash the verdict calls for
+    require!(
+        ctx.accounts.vouched_for_profile.open_disputes == 0,
+        ReputationError::AgentUnderDispute
+    );
+
+    let clock = Clock::get()?;
+    require!(
+        !ctx.accounts.vouch_record.is_expired(clock.unix_timestamp),
+        ReputationError::VouchExpired
+    );
+    let reward_per_share = ctx.accounts.vouched_for_profile.reward_per_share;
+    let amount = ctx.accounts.vouch_record.amount;
+    let created_at = ctx.accounts.vouch_record.created_at;
+    let lockup = ctx.accounts.config.vouch_lockup_period;
+
+    let fee = if ctx.accounts.vouch_record.is_positive {
+        // Positive -> negative: settle rewards and stop earning. The stake stays
+        // in escrow under its existing lockup, so flipping is not a way out early.
+        let pending = calculate_pending_reward(
+            amount,
+            reward_per_share,
+            ctx.accounts.vouch_record.reward_debt,
+        );
+        pay_vouch_reward(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.voucher_token_account,
+            &ctx.accounts.mint_authority,
+            ctx.bumps.mint_authority,
+            pending,
+        )?;
+
+        let target = &mut ctx.accounts.vouched_for_profile;
+        target.positive_vouches = target.positive_vouches.saturating_sub(1);
+        target.negative_vouches = target.negative_vouches.saturating_add(1);
+        target.total_vouch_stake = target.total_vouch_stake.saturating_sub(amount);
+        // Influence in the new direction ramps up from zero again
+        target.remove_vouch_ramp(true, created_at, clock.unix_timestamp, lockup);
+        target.push_vouch_ramp(false, clock.unix_timestamp, lockup);
+
+        let vouch = &mut ctx.accounts.vouch_record;
+        vouch.is_positive = false;
+        vouch.created_at = clock.unix_timestamp;
+        vouch.reward_debt = 0;
+
+        calculate_fee(amount, ctx.accounts.config.dispute_fee_bps)
+    } else {
+        // Negative -> positive: challenges and vouches flipped negative keep
+        // their stake in escrow. Older challenges never escrowed anything, so
+        // the voucher backs the agent with the same amount now.
+        let mut fee = 0;
+
+        if !ctx.accounts.vouch_record.is_escrowed {
+            fee = calculate_fee(amount, ctx.accounts.config.vouch_fee_bps);
+            let stake = amount.checked_sub(fee).ok_or(ReputationError::MathOverflow)?;
+
+            token::transfer(
+                CpiContext::new(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: ctx.accounts.voucher_token_account.to_account_info(),
+                        to: ctx.accounts.escrow_token_account.to_account_info(),
+                        authority: ctx.accounts.voucher.to_account_info(),
+                    },
+                ),
+                stake,
+            )?;
+
+            let vouch = &mut ctx.accounts.vouch_record;
+            vouch.amount = stake;
+            vouch.is_escrowed = true;
+            vouch.init_tranches(stake, clock.unix_timestamp);
+
+            ctx.accounts.voucher_profile.staked_amount =
+                ctx.accounts.voucher_profile.staked_amount.saturating_add(stake);
+        }
+
+        let vouch = &mut ctx.accounts.vouch_record;
+        vouch.is_positive = true;
+        vouch.created_at = clock.unix_timestamp;
+        vouch.reward_debt = calculate_accrued_reward(vouch.amount, reward_per_share);
+        let stake = vouch.amount;
+
+        let target = &mut ctx.accounts.vouched_for_profile;
+        target.negative_vouches = target.negative_vouches.saturating_sub(1);
+        target.positive_vouches = target.positive_vouches.saturating_add(1);
+        target.total_vouch_stake = target.total_vouch_stake.saturating_add(stake);
+        target.remove_vouch_ramp(false, created_at, clock.unix_timestamp, lockup);
+        target.push_vouch_ramp(true, clock.unix_timestamp, lockup);
+
+        fee
+    };
+    update_tier(&mut ctx.accounts.vouched_for_profile, load_tier_config(&ctx.accounts.tier_config)?.as_ref(), clock.unix_timestamp);
+
+    if fee > 0 {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.voucher_token_account.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: ctx.accounts.voucher.to_account_info(),
+                },
+            ),
+            fee,
+        )?;
+        ctx.accounts.treasury.total_token_fees =
+            ctx.accounts.treasury.total_token_fees.saturating_add(fee);
+    }
+
+    msg!("Vouch revised: {} now {} {} (fee {})",
+        ctx.accounts.voucher.key(),
+        if ctx.accounts.vouch_record.is_positive { "backs" } else { "challenges" },
+        ctx.accounts.vouch_record.vouched_for,
+        fee);
+    Ok(())
+}
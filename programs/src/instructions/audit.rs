@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, VouchRecord, ProtocolConfig};
+use crate::errors::ReputationError;
+use super::tier::{load_tier_config, update_tier};
+
+/// Recompute a target profile's vouch aggregates from its live vouch records.
+/// The records are passed in `remaining_accounts`; the caller is trusted to pass
+/// all of them, so this is restricted to the config authority.
+#[derive(Accounts)]
+pub struct AuditVouchAggregates<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"agent", agent.key().as_ref()],
+        bump = agent_profile.bump,
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    
+    /// CHECK: Tier config PDA, read by `load_tier_config`
+    #[account(
+        seeds = [b"tier_config"],
+        bump,
+    )]
+    pub tier_config: UncheckedAccount<'info>,
+    
+    /// CHECK: Just the pubkey
+    pub agent: UncheckedAccount<'info>,
+}
+
+pub fn audit_vouch_aggregates<'info>(
+    ctx: Context<'_, '_, 'info, 'info, AuditVouchAggregates<'info>>,
+) -> Result<()> {
+    let agent_key = ctx.accounts.agent.key();
+    let now = Clock::get()?.unix_timestamp;
+    let lockup = ctx.accounts.config.vouch_lockup_period;
+    let mut maturing: Vec<(i64, bool)> = Vec::new();
+    let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+    
+    let mut positive_vouches: u64 = 0;
+    let mut negative_vouches: u64 = 0;
+    let mut total_vouch_stake: u64 = 0;
+    
+    for info in ctx.remaining_accounts.iter() {
+        // Owner and discriminator are checked on deserialize
+        let vouch: Account<VouchRecord> = Account::try_from(info)?;
+        
+        require!(vouch.vouched_for == agent_key, ReputationError::InvalidVouchRecord);
+        require!(!seen.contains(info.key), ReputationError::InvalidVouchRecord);
+        
+        let expected = Pubkey::create_program_address(
+            &[b"vouch", vouch.voucher.as_ref(), agent_key.as_ref(), &[vouch.bump]],
+            ctx.program_id,
+        ).map_err(|_| ReputationError::InvalidVouchRecord)?;
+        require_keys_eq!(expected, *info.key, ReputationError::InvalidVouchRecord);
+        // Expired vouches must not count, but expire_vouch takes them off the
+        // counters itself, so skipping them here would remove them twice.
+        // They have to be cranked out before the audit instead.
+        require!(!vouch.is_expired(now), ReputationError::VouchExpired);
+        
+        seen.push(*info.key);
+        
+        if now.saturating_sub(vouch.created_at) < lockup {
+            maturing.push((vouch.created_at, vouch.is_positive));
+        }
+        
+        if vouch.is_positive {
+            positive_vouches = positive_vouches.checked_add(1).ok_or(ReputationError::MathOverflow)?;
+            total_vouch_stake = total_vouch_stake
+                .checked_add(vouch.amount)
+                .ok_or(ReputationError::MathOverflow)?;
+        } else {
+            negative_vouches = negative_vouches.checked_add(1).ok_or(ReputationError::MathOverflow)?;
+        }
+    }
+    
+    let profile = &mut ctx.accounts.agent_profile;
+    msg!("Vouch audit for {}: +{} -{} stake {} -> +{} -{} stake {}",
+        agent_key,
+        profile.positive_vouches, profile.negative_vouches, profile.total_vouch_stake,
+        positive_vouches, negative_vouches, total_vouch_stake);
+    
+    profile.positive_vouches = positive_vouches;
+    profile.negative_vouches = negative_vouches;
+    profile.total_vouch_stake = total_vouch_stake;
+    
+    // Rebuild the influence ramps from the records that are still maturing
+    profile.vouch_ramps = Default::default();
+    maturing.sort_unstable();
+    for (created_at, is_positive) in maturing {
+        profile.push_vouch_ramp(is_positive, created_at, lockup);
+    }
+    update_tier(profile, load_tier_config(&ctx.accounts.tier_config)?.as_ref(), now);
+    
+    Ok(())
+}
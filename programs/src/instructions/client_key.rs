@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+use crate::state::{ClientKey, ProtocolConfig};
+use crate::errors::ReputationError;
+
+#[derive(Accounts)]
+#[instruction(key: Pubkey)]
+pub struct RegisterClientKey<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = ClientKey::LEN,
+        seeds = [b"client_key", key.as_ref()],
+        bump
+    )]
+    pub client_key: Account<'info, ClientKey>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn register_client_key(ctx: Context<RegisterClientKey>, key: Pubkey) -> Result<()> {
+    let client_key = &mut ctx.accounts.client_key;
+    client_key.key = key;
+    client_key.is_active = true;
+    client_key.registered_at = Clock::get()?.unix_timestamp;
+    client_key.bump = ctx.bumps.client_key;
+
+    msg!("Client key registered: {}", key);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetClientKeyActive<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"client_key", client_key.key.as_ref()],
+        bump = client_key.bump,
+    )]
+    pub client_key: Account<'info, ClientKey>,
+}
+
+pub fn set_client_key_active(ctx: Context<SetClientKeyActive>, is_active: bool) -> Result<()> {
+    let client_key = &mut ctx.accounts.client_key;
+    client_key.is_active = is_active;
+
+    msg!("Client key {} active: {}", client_key.key, is_active);
+    Ok(())
+}
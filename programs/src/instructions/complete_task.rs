@@ -1,10 +1,13 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, MintTo, TokenAccount};
-use crate::state::{AgentProfile, TaskRecord, ProtocolConfig};
+use anchor_lang::solana_program::sysvar::instructions::{self as instructions_sysvar, get_instruction_relative};
+use crate::state::{AgentProfile, TaskRecord, ProtocolConfig, SkillCategory, DomainReputation, ClientKey, calculate_fee, REWARD_PRECISION, PAUSE_MINTING};
+use crate::receipt::{TaskReceipt, receipt_message, is_ed25519_verification, MAX_RECEIPT_AGE};
 use crate::errors::ReputationError;
+use super::tier::{load_tier_config, update_tier};
 
 #[derive(Accounts)]
-#[instruction(task_id: String, reputation_amount: u64)]
+#[instruction(task_id: String, reputation_amount: u64, category_id: u32)]
 pub struct CompleteTask<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
@@ -16,6 +19,13 @@ pub struct CompleteTask<'info> {
     )]
     pub agent_profile: Account<'info, AgentProfile>,
     
+    /// CHECK: Tier config PDA, read by `load_tier_config`
+    #[account(
+        seeds = [b"tier_config"],
+        bump,
+    )]
+    pub tier_config: UncheckedAccount<'info>,
+    
     #[account(
         init,
         payer = authority,
@@ -26,11 +36,27 @@ pub struct CompleteTask<'info> {
     pub task_record: Account<'info, TaskRecord>,
     
     #[account(
+        mut,
         seeds = [b"config"],
         bump = config.bump,
     )]
     pub config: Account<'info, ProtocolConfig>,
     
+    #[account(
+        seeds = [b"category", category_id.to_le_bytes().as_ref()],
+        bump = category.bump,
+    )]
+    pub category: Account<'info, SkillCategory>,
+    
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = DomainReputation::LEN,
+        seeds = [b"domain", authority.key().as_ref(), category_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub domain_reputation: Account<'info, DomainReputation>,
+    
     /// CHECK: This is the token mint authority PDA
     #[account(
         seeds = [b"mint_authority"],
@@ -51,6 +77,24 @@ pub struct CompleteTask<'info> {
     )]
     pub agent_token_account: Account<'info, TokenAccount>,
     
+    #[account(
+        mut,
+        seeds = [b"reward_vault"],
+        bump,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    /// Client key that signed the receipt, when redeeming one
+    #[account(
+        seeds = [b"client_key", client_key.key.as_ref()],
+        bump = client_key.bump,
+    )]
+    pub client_key: Option<Account<'info, ClientKey>>,
+    
+    /// CHECK: Instructions sysvar, used to find the Ed25519 verification
+    #[account(address = instructions_sysvar::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+    
     pub token_program: Program<'info, token::Token>,
     pub system_program: Program<'info, System>,
 }
@@ -59,19 +103,36 @@ pub fn complete_task(
     ctx: Context<CompleteTask>,
     task_id: String,
     reputation_amount: u64,
+    category_id: u32,
+    receipt: Option<TaskReceipt>,
 ) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_MINTING)?;
+    
     require!(task_id.len() <= 100, ReputationError::TaskIdTooLong);
     require!(reputation_amount > 0, ReputationError::InvalidReputationAmount);
     
-    let profile = &mut ctx.accounts.agent_profile;
+    let profile = &ctx.accounts.agent_profile;
     let clock = Clock::get()?;
     
     require!(profile.is_active, ReputationError::AgentInactive);
+    require!(ctx.accounts.category.is_active, ReputationError::CategoryInactive);
     
-    // Update profile
-    profile.reputation_score = profile.reputation_score.saturating_add(reputation_amount);
-    profile.total_tasks_completed = profile.total_tasks_completed.saturating_add(1);
-    profile.last_activity_timestamp = clock.unix_timestamp;
+    let requester = match &receipt {
+        Some(receipt) => {
+            verify_receipt(&ctx, receipt, &task_id, reputation_amount, category_id, clock.unix_timestamp)?;
+            receipt.client
+        }
+        None => Pubkey::default(),
+    };
+    
+    credit_task_reputation(
+        &mut ctx.accounts.agent_profile,
+        &mut ctx.accounts.domain_reputation,
+        ctx.bumps.domain_reputation,
+        category_id,
+        reputation_amount,
+        clock.unix_timestamp,
+    );
     
     // Create task record
     let task = &mut ctx.accounts.task_record;
@@ -79,25 +140,152 @@ pub fn complete_task(
     task.task_id = task_id.clone();
     task.reputation_earned = reputation_amount;
     task.completed_at = clock.unix_timestamp;
+    task.category_id = category_id;
+    task.requester = requester;
+    task.rating = 0;
+    task.review_hash = [0; 32];
+    task.missed_deadline = false;
+    task.reputation_penalty = 0;
+    task.posted_task = Pubkey::default();
     task.bump = ctx.bumps.task_record;
     
-    // Mint reputation tokens to agent
-    let seeds = &[b"mint_authority", &[ctx.bumps.mint_authority]];
-    let signer = &[&seeds[..]];
+    mint_task_reputation(
+        &mut ctx.accounts.agent_profile,
+        &mut ctx.accounts.config,
+        &ctx.accounts.token_program,
+        &ctx.accounts.reputation_mint,
+        &ctx.accounts.agent_token_account,
+        &ctx.accounts.reward_vault,
+        &ctx.accounts.mint_authority,
+        ctx.bumps.mint_authority,
+        reputation_amount,
+        clock.unix_timestamp,
+    )?;
+    update_tier(&mut ctx.accounts.agent_profile, load_tier_config(&ctx.accounts.tier_config)?.as_ref(), clock.unix_timestamp);
+    
+    msg!("Task completed: {} | Category: {} | Reputation earned: {}", task_id, category_id, reputation_amount);
+    Ok(())
+}
+
+/// Check that the transaction verified `receipt` with the Ed25519 precompile
+/// in the instruction right before this one
+fn verify_receipt(
+    ctx: &Context<CompleteTask>,
+    receipt: &TaskReceipt,
+    task_id: &str,
+    reputation_amount: u64,
+    category_id: u32,
+    now: i64,
+) -> Result<()> {
+    let client_key = ctx.accounts.client_key.as_ref().ok_or(ReputationError::InvalidReceipt)?;
+    let instructions = ctx.accounts.instructions.as_ref().ok_or(ReputationError::InvalidReceipt)?;
+    
+    require_keys_eq!(client_key.key, receipt.client, ReputationError::InvalidReceipt);
+    require!(client_key.is_active, ReputationError::ClientKeyInactive);
+    require!(receipt.timestamp <= now, ReputationError::InvalidReceipt);
+    require!(now - receipt.timestamp <= MAX_RECEIPT_AGE, ReputationError::ReceiptExpired);
+    
+    let message = receipt_message(
+        task_id,
+        &ctx.accounts.authority.key(),
+        reputation_amount,
+        category_id,
+        receipt.timestamp,
+        receipt.nonce,
+    );
+    let verify_ix = get_instruction_relative(-1, &instructions.to_account_info())
+        .map_err(|_| error!(ReputationError::InvalidReceipt))?;
+    require!(
+        is_ed25519_verification(&verify_ix, &receipt.client, &message),
+        ReputationError::InvalidReceipt
+    );
+    
+    Ok(())
+}
+
+/// Credit a completed task to the agent's global and domain scores
+pub(crate) fn credit_task_reputation(
+    profile: &mut AgentProfile,
+    domain: &mut DomainReputation,
+    domain_bump: u8,
+    category_id: u32,
+    reputation_amount: u64,
+    now: i64,
+) {
+    profile.set_reputation_score(profile.reputation_score.saturating_add(reputation_amount), now);
+    profile.total_tasks_completed = profile.total_tasks_completed.saturating_add(1);
+    profile.last_activity_timestamp = now;
+    
+    // The domain account may have just been created by init_if_needed
+    if domain.agent == Pubkey::default() {
+        domain.agent = profile.owner;
+        domain.category_id = category_id;
+        domain.bump = domain_bump;
+    }
+    domain.score = domain.score.saturating_add(reputation_amount);
+    domain.tasks_completed = domain.tasks_completed.saturating_add(1);
+    domain.last_activity_timestamp = now;
+}
+
+/// Mint task reputation, splitting off the voucher share into the reward vault.
+/// Fails if the mint would exceed the global or the agent's epoch cap.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn mint_task_reputation<'info>(
+    profile: &mut Account<'info, AgentProfile>,
+    config: &mut Account<'info, ProtocolConfig>,
+    token_program: &Program<'info, token::Token>,
+    reputation_mint: &Account<'info, token::Mint>,
+    agent_token_account: &Account<'info, TokenAccount>,
+    reward_vault: &Account<'info, TokenAccount>,
+    mint_authority: &UncheckedAccount<'info>,
+    mint_authority_bump: u8,
+    reputation_amount: u64,
+    now: i64,
+) -> Result<()> {
+    config.record_mint(profile, reputation_amount, now)?;
+    
+    // Vouchers backing this agent get a share of the mint, pro-rata to stake
+    let voucher_share = if profile.total_vouch_stake > 0 {
+        calculate_fee(reputation_amount, config.voucher_reward_bps)
+    } else {
+        0
+    };
+    if voucher_share > 0 {
+        let increment = (voucher_share as u128) * REWARD_PRECISION / (profile.total_vouch_stake as u128);
+        profile.reward_per_share = profile.reward_per_share.saturating_add(increment);
+    }
+    let agent_share = reputation_amount - voucher_share;
+    
+    let seeds: &[&[u8]] = &[b"mint_authority", &[mint_authority_bump]];
+    let signer = &[seeds];
     
     token::mint_to(
         CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
+            token_program.to_account_info(),
             MintTo {
-                mint: ctx.accounts.reputation_mint.to_account_info(),
-                to: ctx.accounts.agent_token_account.to_account_info(),
-                authority: ctx.accounts.mint_authority.to_account_info(),
+                mint: reputation_mint.to_account_info(),
+                to: agent_token_account.to_account_info(),
+                authority: mint_authority.to_account_info(),
             },
             signer,
         ),
-        reputation_amount,
+        agent_share,
     )?;
     
-    msg!("Task completed: {} | Reputation earned: {}", task_id, reputation_amount);
+    if voucher_share > 0 {
+        token::mint_to(
+            CpiContext::new_with_signer(
+                token_program.to_account_info(),
+                MintTo {
+                    mint: reputation_mint.to_account_info(),
+                    to: reward_vault.to_account_info(),
+                    authority: mint_authority.to_account_info(),
+                },
+                signer,
+            ),
+            voucher_share,
+        )?;
+    }
+    
     Ok(())
 }
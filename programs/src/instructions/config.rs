@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use crate::state::{ProtocolConfig, PAUSE_ALL};
+use crate::errors::ReputationError;
+
+#[derive(Accounts)]
+pub struct UpdateConfig<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+#[derive(Accounts)]
+pub struct Pause<'info> {
+    /// The guardian or the authority
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = signer.key() == config.guardian || signer.key() == config.authority
+            @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+/// Pause one or more instruction groups (bitmask of PAUSE_* flags)
+pub fn pause(ctx: Context<Pause>, groups: u8) -> Result<()> {
+    require!(groups != 0 && groups & !PAUSE_ALL == 0, ReputationError::InvalidPauseGroups);
+
+    let config = &mut ctx.accounts.config;
+    config.paused |= groups;
+
+    msg!("Paused groups {:#06b} by {} | now paused: {:#06b}", groups, ctx.accounts.signer.key(), config.paused);
+    Ok(())
+}
+
+/// Unpause instruction groups. Authority only; the guardian can't undo a pause.
+pub fn unpause(ctx: Context<UpdateConfig>, groups: u8) -> Result<()> {
+    require!(groups != 0 && groups & !PAUSE_ALL == 0, ReputationError::InvalidPauseGroups);
+
+    let config = &mut ctx.accounts.config;
+    config.paused &= !groups;
+
+    msg!("Unpaused groups {:#06b} | now paused: {:#06b}", groups, config.paused);
+    Ok(())
+}
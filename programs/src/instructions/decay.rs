@@ -1,6 +1,8 @@
 use anchor_lang::prelude::*;
-use crate::state::{AgentProfile, ProtocolConfig, calculate_decay};
+use anchor_spl::token::{self, TokenAccount, Transfer};
+use crate::state::{AgentProfile, ProtocolConfig, Treasury, calculate_decay_days, calculate_profile_decay, PAUSE_MINTING, PAUSE_WITHDRAWALS};
 use crate::errors::ReputationError;
+use super::tier::{load_tier_config, update_tier};
 
 #[derive(Accounts)]
 pub struct ApplyDecay<'info> {
@@ -14,6 +16,13 @@ pub struct ApplyDecay<'info> {
     )]
     pub agent_profile: Account<'info, AgentProfile>,
     
+    /// CHECK: Tier config PDA, read by `load_tier_config`
+    #[account(
+        seeds = [b"tier_config"],
+        bump,
+    )]
+    pub tier_config: UncheckedAccount<'info>,
+    
     /// CHECK: Just the pubkey
     pub agent: UncheckedAccount<'info>,
     
@@ -25,30 +34,147 @@ pub struct ApplyDecay<'info> {
 }
 
 pub fn apply_decay(ctx: Context<ApplyDecay>) -> Result<()> {
-    let profile = &mut ctx.accounts.agent_profile;
+    ctx.accounts.config.require_not_paused(PAUSE_MINTING)?;
+    
     let clock = Clock::get()?;
-    let config = &ctx.accounts.config;
+    let profile = &mut ctx.accounts.agent_profile;
     
-    let days_since_activity = (clock.unix_timestamp.saturating_sub(profile.last_activity_timestamp)) / 86400;
+    let (old_reputation, new_reputation, days_decayed) =
+        decay_profile(profile, &ctx.accounts.config, clock.unix_timestamp)
+            .ok_or(ReputationError::DecayCooldown)?;
+    update_tier(profile, load_tier_config(&ctx.accounts.tier_config)?.as_ref(), clock.unix_timestamp);
     
-    require!(days_since_activity > 0, ReputationError::DecayCooldown);
+    msg!("Decay applied: {} -> {} ({} days decayed)", 
+        old_reputation, new_reputation, days_decayed);
     
-    let old_reputation = profile.reputation_score;
-    let new_reputation = calculate_decay(
-        profile.reputation_score,
-        days_since_activity,
-        config.decay_rate_per_day,
-    );
+    Ok(())
+}
+
+/// Decay a profile for every whole day since its last activity or decay,
+/// past the grace period and down to its floor. Returns the old and new
+/// scores and the days decayed, or `None` if nothing is owed yet.
+pub(crate) fn decay_profile(profile: &mut AgentProfile, config: &ProtocolConfig, now: i64) -> Option<(u64, u64, i64)> {
+    let days = calculate_decay_days(profile.last_activity_timestamp, now, config.decay_grace_days);
+    if days <= 0 {
+        return None;
+    }
     
-    profile.reputation_score = new_reputation;
+    let old_reputation = profile.reputation_score;
+    let new_reputation = calculate_profile_decay(old_reputation, days, profile.total_tasks_completed, config);
+    profile.set_reputation_score(new_reputation, now);
+    // Restart the clock by the days decayed only, so they aren't decayed
+    // twice and the grace period isn't granted again
+    profile.last_activity_timestamp = profile.last_activity_timestamp
+        .saturating_add(days * 86400);
     
     // Mark as inactive if reputation drops too low
     if profile.reputation_score < 10 {
         profile.is_active = false;
     }
     
-    msg!("Decay applied: {} -> {} ({} days inactive)", 
-        old_reputation, new_reputation, days_since_activity);
+    Some((old_reputation, new_reputation, days))
+}
+
+#[derive(Accounts)]
+pub struct ApplyDecayBatch<'info> {
+    /// Anyone can crank decay and collect the keeper reward
+    pub keeper: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    
+    /// CHECK: Tier config PDA, read by `load_tier_config`
+    #[account(
+        seeds = [b"tier_config"],
+        bump,
+    )]
+    pub tier_config: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    
+    #[account(
+        mut,
+        address = treasury.vault,
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        token::mint = config.reputation_mint,
+        token::authority = keeper,
+    )]
+    pub keeper_token_account: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, token::Token>,
+}
+
+/// Apply decay to every `AgentProfile` passed in remaining accounts (writable).
+/// Profiles that aren't due yet, or whose score doesn't move, are skipped.
+/// The keeper earns `decay_keeper_reward` per decayed profile.
+pub fn apply_decay_batch<'info>(ctx: Context<'_, '_, 'info, 'info, ApplyDecayBatch<'info>>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_MINTING)?;
+    
+    let now = Clock::get()?.unix_timestamp;
+    let tier_config = load_tier_config(&ctx.accounts.tier_config)?;
+    let mut decayed: u64 = 0;
+    
+    for info in ctx.remaining_accounts.iter() {
+        let mut profile: Account<AgentProfile> = Account::try_from(info)
+            .map_err(|_| error!(ReputationError::InvalidAgentProfile))?;
+        let expected = Pubkey::create_program_address(
+            &[b"agent", profile.owner.as_ref(), &[profile.bump]],
+            ctx.program_id,
+        )
+        .map_err(|_| error!(ReputationError::InvalidAgentProfile))?;
+        require_keys_eq!(expected, *info.key, ReputationError::InvalidAgentProfile);
+        require!(info.is_writable, ReputationError::InvalidAgentProfile);
+        
+        let Some((old_reputation, new_reputation, _)) = decay_profile(&mut profile, &ctx.accounts.config, now) else {
+            continue;
+        };
+        update_tier(&mut profile, tier_config.as_ref(), now);
+        // Written back right away so a repeated account sees the new state
+        profile.exit(ctx.program_id)?;
+        
+        if new_reputation < old_reputation {
+            decayed += 1;
+        }
+    }
+    
+    // The reward is a withdrawal, so it stops while withdrawals are paused
+    let reward = if ctx.accounts.config.paused & PAUSE_WITHDRAWALS == 0 {
+        decayed
+            .saturating_mul(ctx.accounts.config.decay_keeper_reward)
+            .min(ctx.accounts.treasury_vault.amount)
+    } else {
+        0
+    };
+    if reward > 0 {
+        let seeds: &[&[u8]] = &[b"treasury", &[ctx.accounts.treasury.bump]];
+        let signer = &[seeds];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.treasury_vault.to_account_info(),
+                    to: ctx.accounts.keeper_token_account.to_account_info(),
+                    authority: ctx.accounts.treasury.to_account_info(),
+                },
+                signer,
+            ),
+            reward,
+        )?;
+    }
     
+    msg!("Batch decay: {} of {} profiles decayed | Keeper reward: {}", 
+        decayed, ctx.remaining_accounts.len(), reward);
     Ok(())
 }
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use crate::state::{DomainReputation, ProtocolConfig, SkillCategory, PAUSE_MINTING};
+use crate::errors::ReputationError;
+
+#[derive(Accounts)]
+#[instruction(category_id: u32)]
+pub struct CreateCategory<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = SkillCategory::LEN,
+        seeds = [b"category", category_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub category: Account<'info, SkillCategory>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_category(ctx: Context<CreateCategory>, category_id: u32, name: String) -> Result<()> {
+    require!(name.len() <= 32, ReputationError::CategoryNameTooLong);
+    
+    let category = &mut ctx.accounts.category;
+    category.id = category_id;
+    category.name = name;
+    category.is_active = true;
+    category.bump = ctx.bumps.category;
+    
+    msg!("Skill category created: {} ({})", category.name, category_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SetCategoryActive<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"category", category.id.to_le_bytes().as_ref()],
+        bump = category.bump,
+    )]
+    pub category: Account<'info, SkillCategory>,
+}
+
+pub fn set_category_active(ctx: Context<SetCategoryActive>, is_active: bool) -> Result<()> {
+    let category = &mut ctx.accounts.category;
+    category.is_active = is_active;
+    
+    msg!("Skill category {} active: {}", category.id, is_active);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ApplyDomainDecay<'info> {
+    /// Anyone can call this to apply decay to inactive domains
+    pub caller: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"domain", domain_reputation.agent.as_ref(), domain_reputation.category_id.to_le_bytes().as_ref()],
+        bump = domain_reputation.bump,
+    )]
+    pub domain_reputation: Account<'info, DomainReputation>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+pub fn apply_domain_decay(ctx: Context<ApplyDomainDecay>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_MINTING)?;
+    
+    let domain = &mut ctx.accounts.domain_reputation;
+    let clock = Clock::get()?;
+    
+    let (days_decayed, new_score) = domain.pending_decay(clock.unix_timestamp, &ctx.accounts.config);
+    
+    require!(days_decayed > 0, ReputationError::DecayCooldown);
+    
+    let old_score = domain.score;
+    domain.score = new_score;
+    // Restart the clock by the days decayed, keeping any grace period intact
+    domain.last_activity_timestamp = domain.last_activity_timestamp
+        .saturating_add(days_decayed * 86400);
+    
+    msg!("Domain decay applied: category {} | {} -> {} ({} days decayed)", 
+        domain.category_id, old_score, domain.score, days_decayed);
+    Ok(())
+}
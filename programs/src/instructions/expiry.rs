@@ -0,0 +1,191 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, TokenAccount, Transfer};
+use crate::state::{AgentProfile, VouchRecord, ProtocolConfig, calculate_pending_reward, PAUSE_VOUCHING, PAUSE_WITHDRAWALS};
+use crate::errors::ReputationError;
+use super::tier::{load_tier_config, update_tier};
+use crate::instructions::rewards::pay_vouch_reward;
+
+#[derive(Accounts)]
+pub struct ExpireVouch<'info> {
+    /// Anyone can crank an expired vouch closed
+    pub caller: Signer<'info>,
+    
+    /// CHECK: Receives the refund and rent; verified by the record's seeds
+    #[account(mut)]
+    pub voucher: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"agent", voucher.key().as_ref()],
+        bump = voucher_profile.bump,
+    )]
+    pub voucher_profile: Account<'info, AgentProfile>,
+    
+    #[account(
+        mut,
+        close = voucher,
+        seeds = [b"vouch", voucher.key().as_ref(), vouch_record.vouched_for.as_ref()],
+        bump = vouch_record.bump,
+    )]
+    pub vouch_record: Account<'info, VouchRecord>,
+    
+    #[account(
+        mut,
+        seeds = [b"agent", vouch_record.vouched_for.as_ref()],
+        bump = vouched_for_profile.bump,
+    )]
+    pub vouched_for_profile: Account<'info, AgentProfile>,
+    
+    /// CHECK: Tier config PDA, read by `load_tier_config`
+    #[account(
+        seeds = [b"tier_config"],
+        bump,
+    )]
+    pub tier_config: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    
+    #[account(
+        mut,
+        associated_token::mint = config.reputation_mint,
+        associated_token::authority = voucher,
+    )]
+    pub voucher_token_account: Account<'info, TokenAccount>,
+    
+    /// Only needed when the vouch has escrowed stake
+    #[account(
+        mut,
+        associated_token::mint = config.reputation_mint,
+        associated_token::authority = vouch_escrow,
+    )]
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
+    
+    /// CHECK: PDA that owns the escrow account
+    #[account(
+        seeds = [b"escrow", vouch_record.key().as_ref()],
+        bump,
+    )]
+    pub vouch_escrow: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"reward_vault"],
+        bump,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    /// CHECK: This is the token mint authority PDA
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, token::Token>,
+}
+
+pub fn expire_vouch(ctx: Context<ExpireVouch>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_WITHDRAWALS)?;
+    require!(
+        ctx.accounts.vouched_for_profile.open_disputes == 0,
+        ReputationError::AgentUnderDispute
+    );
+    
+    let vouch = &ctx.accounts.vouch_record;
+    let clock = Clock::get()?;
+    
+    require!(vouch.is_expired(clock.unix_timestamp), ReputationError::VouchNotExpired);
+    
+    // Escrow goes back to the voucher, lockup no longer applies
+    if vouch.amount > 0 && vouch.is_escrowed {
+        let escrow_token_account = ctx.accounts.escrow_token_account
+            .as_ref()
+            .ok_or(ReputationError::NoEscrowedStake)?;
+        let vouch_key = vouch.key();
+        let seeds = &[b"escrow", vouch_key.as_ref(), &[ctx.bumps.vouch_escrow]];
+        let signer = &[&seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: escrow_token_account.to_account_info(),
+                    to: ctx.accounts.voucher_token_account.to_account_info(),
+                    authority: ctx.accounts.vouch_escrow.to_account_info(),
+                },
+                signer,
+            ),
+            vouch.amount,
+        )?;
+    }
+    
+    if vouch.is_positive {
+        let pending = calculate_pending_reward(
+            vouch.amount,
+            ctx.accounts.vouched_for_profile.reward_per_share,
+            vouch.reward_debt,
+        );
+        pay_vouch_reward(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.voucher_token_account,
+            &ctx.accounts.mint_authority,
+            ctx.bumps.mint_authority,
+            pending,
+        )?;
+    }
+    
+    ctx.accounts.vouched_for_profile.remove_vouch(vouch, clock.unix_timestamp, ctx.accounts.config.vouch_lockup_period);
+    update_tier(&mut ctx.accounts.vouched_for_profile, load_tier_config(&ctx.accounts.tier_config)?.as_ref(), clock.unix_timestamp);
+    if vouch.is_escrowed {
+        ctx.accounts.voucher_profile.staked_amount = 
+            ctx.accounts.voucher_profile.staked_amount.saturating_sub(vouch.amount);
+    }
+    
+    msg!("Vouch expired: {} -> {} | Returned {} tokens", 
+        vouch.voucher, vouch.vouched_for, if vouch.is_escrowed { vouch.amount } else { 0 });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RenewVouch<'info> {
+    pub voucher: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"vouch", voucher.key().as_ref(), vouch_record.vouched_for.as_ref()],
+        bump = vouch_record.bump,
+    )]
+    pub vouch_record: Account<'info, VouchRecord>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+pub fn renew_vouch(ctx: Context<RenewVouch>, expires_at: Option<i64>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_VOUCHING)?;
+    
+    let clock = Clock::get()?;
+    let vouch = &mut ctx.accounts.vouch_record;
+    
+    require!(!vouch.is_expired(clock.unix_timestamp), ReputationError::VouchExpired);
+    
+    let expires_at = ctx.accounts.config.validate_vouch_expiry(expires_at, clock.unix_timestamp)?;
+    // Renewal only ever extends
+    require!(
+        expires_at == 0 || (vouch.expires_at != 0 && expires_at > vouch.expires_at),
+        ReputationError::InvalidVouchExpiry
+    );
+    
+    vouch.expires_at = expires_at;
+    
+    msg!("Vouch renewed: {} -> {} until {}", vouch.voucher, vouch.vouched_for, expires_at);
+    Ok(())
+}
@@ -0,0 +1,547 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::{program::invoke, program::invoke_signed, system_instruction};
+use crate::state::{
+    AgentProfile, ConfigChange, Proposal, ProposalStatus, ProtocolConfig, QueuedProposal, Timelock, VoteDelegation,
+    VoteRecord, calculate_effective_score, PAUSE_GOVERNANCE,
+};
+use crate::errors::ReputationError;
+
+#[derive(Accounts)]
+pub struct InitializeTimelock<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Timelock::LEN,
+        seeds = [b"timelock"],
+        bump
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn initialize_timelock(
+    ctx: Context<InitializeTimelock>,
+    delay: i64,
+    voting_period: i64,
+    quorum: u64,
+    proposal_threshold: u64,
+) -> Result<()> {
+    require!(delay > 0 && voting_period > 0, ReputationError::InvalidGovernanceParams);
+
+    let timelock = &mut ctx.accounts.timelock;
+    timelock.delay = delay;
+    timelock.voting_period = voting_period;
+    timelock.quorum = quorum;
+    timelock.proposal_threshold = proposal_threshold;
+    timelock.proposal_count = 0;
+    timelock.queue = Default::default();
+    timelock.bump = ctx.bumps.timelock;
+
+    msg!("Timelock initialized: delay {}s | voting period {}s", delay, voting_period);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateProposal<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    #[account(
+        seeds = [b"agent", proposer.key().as_ref()],
+        bump = proposer_profile.bump,
+    )]
+    pub proposer_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"timelock"],
+        bump = timelock.bump,
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = Proposal::LEN,
+        seeds = [b"proposal", timelock.proposal_count.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn create_proposal(
+    ctx: Context<CreateProposal>,
+    change: ConfigChange,
+    description_hash: [u8; 32],
+) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE)?;
+    change.validate()?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let profile = &ctx.accounts.proposer_profile;
+    require!(profile.is_active, ReputationError::AgentInactive);
+    require!(
+        calculate_effective_score(profile, now, &ctx.accounts.config) >= ctx.accounts.timelock.proposal_threshold,
+        ReputationError::InsufficientReputation
+    );
+
+    let timelock = &mut ctx.accounts.timelock;
+    let proposal = &mut ctx.accounts.proposal;
+    proposal.id = timelock.proposal_count;
+    proposal.proposer = ctx.accounts.proposer.key();
+    proposal.change = change;
+    proposal.description_hash = description_hash;
+    proposal.created_at = now;
+    proposal.voting_ends_at = now.saturating_add(timelock.voting_period);
+    proposal.votes_for = 0;
+    proposal.votes_against = 0;
+    proposal.status = ProposalStatus::Active;
+    proposal.eta = 0;
+    proposal.bump = ctx.bumps.proposal;
+
+    timelock.proposal_count = timelock.proposal_count.saturating_add(1);
+
+    msg!("Proposal {} created by {}", proposal.id, proposal.proposer);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CastVote<'info> {
+    #[account(mut)]
+    pub voter: Signer<'info>,
+
+    #[account(
+        seeds = [b"agent", voter.key().as_ref()],
+        bump = voter_profile.bump,
+    )]
+    pub voter_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        init,
+        payer = voter,
+        space = VoteRecord::LEN,
+        seeds = [b"vote", proposal.key().as_ref(), voter.key().as_ref()],
+        bump
+    )]
+    pub vote_record: Account<'info, VoteRecord>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Vote with the voter's score as of the proposal's creation, so weight can't
+/// be bought or pumped once a proposal is up. Agents that delegated
+/// to the voter are counted by passing (delegation, delegator profile,
+/// delegator vote record) triples in remaining accounts; each gets its own
+/// vote record so it can't be counted twice.
+pub fn cast_vote<'info>(ctx: Context<'_, '_, 'info, 'info, CastVote<'info>>, support: bool) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let proposal_key = ctx.accounts.proposal.key();
+    let voter_key = ctx.accounts.voter.key();
+    let proposal = &ctx.accounts.proposal;
+    require!(proposal.status == ProposalStatus::Active, ReputationError::InvalidProposalStatus);
+    require!(now < proposal.voting_ends_at, ReputationError::VotingClosed);
+
+    let profile = &ctx.accounts.voter_profile;
+    require!(profile.is_active, ReputationError::AgentInactive);
+    let own_weight = profile
+        .reputation_at(proposal.created_at)
+        .ok_or(ReputationError::HistoryUnavailable)?;
+
+    let delegations = ctx.remaining_accounts.chunks_exact(3);
+    require!(delegations.remainder().is_empty(), ReputationError::InvalidDelegation);
+    let mut delegated_weight: u64 = 0;
+    for accounts in delegations {
+        let (delegation_info, delegator_profile_info, vote_record_info) = (&accounts[0], &accounts[1], &accounts[2]);
+
+        // Owner and discriminator are checked on deserialize
+        let delegation: Account<VoteDelegation> = Account::try_from(delegation_info)?;
+        require_keys_eq!(delegation.delegate, voter_key, ReputationError::InvalidDelegation);
+        let expected = Pubkey::create_program_address(
+            &[b"delegation", delegation.delegator.as_ref(), &[delegation.bump]],
+            ctx.program_id,
+        ).map_err(|_| ReputationError::InvalidDelegation)?;
+        require_keys_eq!(expected, *delegation_info.key, ReputationError::InvalidDelegation);
+
+        let delegator_profile: Account<AgentProfile> = Account::try_from(delegator_profile_info)?;
+        let expected = Pubkey::create_program_address(
+            &[b"agent", delegation.delegator.as_ref(), &[delegator_profile.bump]],
+            ctx.program_id,
+        ).map_err(|_| ReputationError::InvalidDelegation)?;
+        require_keys_eq!(expected, *delegator_profile_info.key, ReputationError::InvalidDelegation);
+
+        // Only the delegator's own score moves; its delegators stay with it
+        let weight = if delegator_profile.is_active {
+            delegator_profile
+                .reputation_at(proposal.created_at)
+                .ok_or(ReputationError::HistoryUnavailable)?
+        } else {
+            0
+        };
+
+        create_vote_record(
+            &ctx.accounts.voter.to_account_info(),
+            vote_record_info,
+            &ctx.accounts.system_program.to_account_info(),
+            ctx.program_id,
+            VoteRecord {
+                proposal: proposal_key,
+                voter: delegation.delegator,
+                support,
+                weight,
+                delegate: voter_key,
+                bump: 0,
+            },
+        )?;
+
+        delegated_weight = delegated_weight.checked_add(weight).ok_or(ReputationError::MathOverflow)?;
+    }
+
+    let weight = own_weight.checked_add(delegated_weight).ok_or(ReputationError::MathOverflow)?;
+    require!(weight > 0, ReputationError::NoVotingPower);
+
+    let proposal = &mut ctx.accounts.proposal;
+    if support {
+        proposal.votes_for = proposal.votes_for.saturating_add(weight);
+    } else {
+        proposal.votes_against = proposal.votes_against.saturating_add(weight);
+    }
+
+    let vote = &mut ctx.accounts.vote_record;
+    vote.proposal = proposal_key;
+    vote.voter = voter_key;
+    vote.support = support;
+    vote.weight = own_weight;
+    vote.delegate = Pubkey::default();
+    vote.bump = ctx.bumps.vote_record;
+
+    msg!("Vote on proposal {}: {} with weight {} ({} delegated)", 
+        proposal.id, if support { "for" } else { "against" }, weight, delegated_weight);
+    Ok(())
+}
+
+/// Create the `[vote, proposal, voter]` PDA for a delegator by hand, since the
+/// number of delegators isn't known to the accounts struct. Fails if the
+/// delegator already has a vote record for this proposal.
+fn create_vote_record<'info>(
+    payer: &AccountInfo<'info>,
+    record_info: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    program_id: &Pubkey,
+    mut record: VoteRecord,
+) -> Result<()> {
+    let (expected, bump) = Pubkey::find_program_address(
+        &[b"vote", record.proposal.as_ref(), record.voter.as_ref()],
+        program_id,
+    );
+    require_keys_eq!(expected, *record_info.key, ReputationError::InvalidDelegation);
+    require!(record_info.data_is_empty(), ReputationError::AlreadyVoted);
+
+    let seeds: &[&[u8]] = &[b"vote", record.proposal.as_ref(), record.voter.as_ref(), &[bump]];
+    let space = VoteRecord::LEN;
+    let required = Rent::get()?.minimum_balance(space);
+
+    // Someone may have sent lamports to the address ahead of time; top it up
+    // and allocate instead of create_account, which would fail
+    let current = record_info.lamports();
+    if current < required {
+        invoke(
+            &system_instruction::transfer(payer.key, record_info.key, required - current),
+            &[payer.clone(), record_info.clone(), system_program.clone()],
+        )?;
+    }
+    invoke_signed(
+        &system_instruction::allocate(record_info.key, space as u64),
+        &[record_info.clone(), system_program.clone()],
+        &[seeds],
+    )?;
+    invoke_signed(
+        &system_instruction::assign(record_info.key, program_id),
+        &[record_info.clone(), system_program.clone()],
+        &[seeds],
+    )?;
+
+    record.bump = bump;
+    let mut data = record_info.try_borrow_mut_data()?;
+    record.try_serialize(&mut &mut data[..])?;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DelegateVotes<'info> {
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+
+    #[account(
+        seeds = [b"agent", delegator.key().as_ref()],
+        bump = delegator_profile.bump,
+    )]
+    pub delegator_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        seeds = [b"agent", delegate_profile.owner.as_ref()],
+        bump = delegate_profile.bump,
+    )]
+    pub delegate_profile: Account<'info, AgentProfile>,
+
+    /// CHECK: The delegate's own delegation PDA; must not exist
+    #[account(
+        seeds = [b"delegation", delegate_profile.owner.as_ref()],
+        bump,
+    )]
+    pub delegate_delegation: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = delegator,
+        space = VoteDelegation::LEN,
+        seeds = [b"delegation", delegator.key().as_ref()],
+        bump
+    )]
+    pub delegation: Account<'info, VoteDelegation>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn delegate_votes(ctx: Context<DelegateVotes>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE)?;
+
+    let delegator = ctx.accounts.delegator.key();
+    let delegate = ctx.accounts.delegate_profile.owner;
+    require_keys_neq!(delegator, delegate, ReputationError::InvalidDelegation);
+    require!(ctx.accounts.delegate_profile.is_active, ReputationError::AgentInactive);
+    require!(ctx.accounts.delegate_delegation.data_is_empty(), ReputationError::DelegationChain);
+
+    let delegation = &mut ctx.accounts.delegation;
+    delegation.delegator = delegator;
+    delegation.delegate = delegate;
+    delegation.created_at = Clock::get()?.unix_timestamp;
+    delegation.bump = ctx.bumps.delegation;
+
+    msg!("Votes delegated: {} -> {}", delegator, delegate);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevokeDelegation<'info> {
+    #[account(mut)]
+    pub delegator: Signer<'info>,
+
+    #[account(
+        mut,
+        close = delegator,
+        seeds = [b"delegation", delegator.key().as_ref()],
+        bump = delegation.bump,
+        has_one = delegator @ ReputationError::Unauthorized,
+    )]
+    pub delegation: Account<'info, VoteDelegation>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+/// Revoke a delegation. Votes the delegate already cast for the delegator stand.
+pub fn revoke_delegation(ctx: Context<RevokeDelegation>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE)?;
+
+    msg!("Delegation revoked: {} -> {}", ctx.accounts.delegator.key(), ctx.accounts.delegation.delegate);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct QueueProposal<'info> {
+    /// Anyone can queue a proposal once voting has ended
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"timelock"],
+        bump = timelock.bump,
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+/// Close voting: a passed proposal goes into the timelock queue, anything
+/// else is marked defeated
+pub fn queue_proposal(ctx: Context<QueueProposal>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let timelock = &mut ctx.accounts.timelock;
+    let proposal = &mut ctx.accounts.proposal;
+    require!(proposal.status == ProposalStatus::Active, ReputationError::InvalidProposalStatus);
+    require!(now >= proposal.voting_ends_at, ReputationError::VotingNotEnded);
+
+    let passed = proposal.votes_for > proposal.votes_against && proposal.votes_for >= timelock.quorum;
+    if !passed {
+        proposal.status = ProposalStatus::Defeated;
+        msg!("Proposal {} defeated: {} for, {} against", proposal.id, proposal.votes_for, proposal.votes_against);
+        return Ok(());
+    }
+
+    proposal.eta = now.saturating_add(timelock.delay);
+    proposal.status = ProposalStatus::Queued;
+    timelock.enqueue(proposal.key(), proposal.eta, now)?;
+
+    msg!("Proposal {} queued, executable at {}", proposal.id, proposal.eta);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExecuteProposal<'info> {
+    /// Anyone can execute a queued proposal after its ETA
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"timelock"],
+        bump = timelock.bump,
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let proposal = &mut ctx.accounts.proposal;
+    require!(proposal.status == ProposalStatus::Queued, ReputationError::InvalidProposalStatus);
+    require!(now >= proposal.eta, ReputationError::TimelockNotExpired);
+
+    // Too late to run: drop it rather than land a change long after the vote
+    let queued = QueuedProposal { proposal: proposal.key(), eta: proposal.eta };
+    if queued.is_expired(now) {
+        ctx.accounts.timelock.drop_expired(now);
+        proposal.status = ProposalStatus::Expired;
+        msg!("Proposal {} expired unexecuted", proposal.id);
+        return Ok(());
+    }
+
+    ctx.accounts.timelock.dequeue(&proposal.key())?;
+    proposal.change.apply(&mut ctx.accounts.config, now)?;
+    proposal.status = ProposalStatus::Executed;
+
+    msg!("Proposal {} executed", proposal.id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelProposal<'info> {
+    /// The guardian or the authority
+    pub signer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"proposal", proposal.id.to_le_bytes().as_ref()],
+        bump = proposal.bump,
+    )]
+    pub proposal: Account<'info, Proposal>,
+
+    #[account(
+        mut,
+        seeds = [b"timelock"],
+        bump = timelock.bump,
+    )]
+    pub timelock: Account<'info, Timelock>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        constraint = signer.key() == config.guardian || signer.key() == config.authority
+            @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+/// Cancel an active or queued proposal. Not subject to the governance pause,
+/// so a hostile proposal can always be stopped.
+pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+    let proposal = &mut ctx.accounts.proposal;
+    match proposal.status {
+        ProposalStatus::Active => {}
+        ProposalStatus::Queued => {
+            // An expired entry may already have been dropped from the queue
+            let now = Clock::get()?.unix_timestamp;
+            let queued = QueuedProposal { proposal: proposal.key(), eta: proposal.eta };
+            if queued.is_expired(now) {
+                ctx.accounts.timelock.drop_expired(now);
+            } else {
+                ctx.accounts.timelock.dequeue(&proposal.key())?;
+            }
+        }
+        _ => return err!(ReputationError::InvalidProposalStatus),
+    }
+    proposal.status = ProposalStatus::Cancelled;
+
+    msg!("Proposal {} cancelled by {}", proposal.id, ctx.accounts.signer.key());
+    Ok(())
+}
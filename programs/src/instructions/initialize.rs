@@ -1,6 +1,6 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::Mint;
-use crate::state::ProtocolConfig;
+use crate::state::{ProtocolConfig, MAX_DECAY_KEEPER_REWARD};
 use crate::errors::ReputationError;
 
 #[derive(Accounts)]
@@ -24,6 +24,12 @@ pub struct Initialize<'info> {
 }
 
 pub fn initialize(ctx: Context<Initialize>, config: ProtocolConfig) -> Result<()> {
+    require!(config.vouch_fee_bps <= 10000, ReputationError::InvalidFee);
+    require!(config.dispute_fee_bps <= 10000, ReputationError::InvalidFee);
+    require!(config.voucher_reward_bps <= 10000, ReputationError::InvalidFee);
+    require!(config.mint_epoch_duration > 0, ReputationError::InvalidMintCap);
+    require!(config.decay_keeper_reward <= MAX_DECAY_KEEPER_REWARD, ReputationError::InvalidKeeperReward);
+    
     let config_account = &mut ctx.accounts.config;
     
     config_account.authority = ctx.accounts.authority.key();
@@ -31,6 +37,29 @@ pub fn initialize(ctx: Context<Initialize>, config: ProtocolConfig) -> Result<()
     config_account.min_reputation_for_vouching = config.min_reputation_for_vouching;
     config_account.decay_rate_per_day = config.decay_rate_per_day;
     config_account.vouch_lockup_period = config.vouch_lockup_period;
+    config_account.vouch_fee_bps = config.vouch_fee_bps;
+    config_account.dispute_fee_bps = config.dispute_fee_bps;
+    config_account.registration_fee = config.registration_fee;
+    config_account.registration_bond = config.registration_bond;
+    config_account.min_registration_tenure = config.min_registration_tenure;
+    config_account.voucher_reward_bps = config.voucher_reward_bps;
+    config_account.min_vouch_duration = config.min_vouch_duration;
+    config_account.max_vouch_duration = config.max_vouch_duration;
+    config_account.missed_deadline_penalty = config.missed_deadline_penalty;
+    config_account.mint_epoch_duration = config.mint_epoch_duration;
+    config_account.global_mint_cap = config.global_mint_cap;
+    config_account.agent_mint_cap = config.agent_mint_cap;
+    config_account.mint_epoch = 0;
+    config_account.epoch_minted = 0;
+    config_account.decay_keeper_reward = config.decay_keeper_reward;
+    config_account.update_decay_policy(
+        config.decay_grace_days,
+        config.reputation_floor,
+        config.floor_per_task,
+        config.max_reputation_floor,
+    )?;
+    config_account.guardian = config.guardian;
+    config_account.paused = 0;
     config_account.bump = ctx.bumps.config;
     
     msg!("Protocol initialized with decay rate: {} bps", config.decay_rate_per_day);
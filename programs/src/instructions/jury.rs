@@ -0,0 +1,1321 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak::hashv;
+use anchor_lang::solana_program::sysvar::slot_hashes;
+use anchor_spl::token::{self, Mint, TokenAccount, Transfer};
+use crate::state::{
+    AgentProfile, Dispute, DisputeKind, Jury, JuryConfig, Juror, ProtocolConfig, Treasury, VouchRecord,
+    jury_vote_commitment, JURY_SEED_DELAY_SLOTS, MAX_JURY_SIZE, PAUSE_GOVERNANCE, PAUSE_WITHDRAWALS, VOTE_AGENT, VOTE_CLAIMANT, VOTE_NONE,
+};
+use crate::errors::ReputationError;
+
+#[derive(Accounts)]
+pub struct InitializeJuryConfig<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = JuryConfig::LEN,
+        seeds = [b"jury_config"],
+        bump
+    )]
+    pub jury_config: Account<'info, JuryConfig>,
+
+    /// Holds every juror's stake
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"juror_vault"],
+        bump,
+        token::mint = reputation_mint,
+        token::authority = jury_config,
+    )]
+    pub juror_vault: Account<'info, TokenAccount>,
+
+    #[account(address = config.reputation_mint)]
+    pub reputation_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, token::Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn initialize_jury_config(
+    ctx: Context<InitializeJuryConfig>,
+    min_juror_reputation: u64,
+    min_juror_stake: u64,
+    jury_size: u8,
+    commit_period: i64,
+    reveal_period: i64,
+    juror_reward: u64,
+    non_reveal_penalty: u64,
+    juror_exit_delay: i64,
+    appeal_window: i64,
+    appeal_bond: u64,
+    dispute_bond: u64,
+    summon_period: i64,
+) -> Result<()> {
+    // Odd sizes avoid ties among jurors who all reveal
+    require!(
+        jury_size > 0 && jury_size % 2 == 1 && jury_size as usize <= MAX_JURY_SIZE,
+        ReputationError::InvalidJuryParams
+    );
+    require!(commit_period > 0 && reveal_period > 0, ReputationError::InvalidJuryParams);
+    // Jurors can't walk away with their stake while a case they sit on is open
+    require!(
+        juror_exit_delay >= commit_period.saturating_add(reveal_period),
+        ReputationError::InvalidJuryParams
+    );
+    require!(appeal_window >= 0, ReputationError::InvalidJuryParams);
+    require!(summon_period > 0, ReputationError::InvalidJuryParams);
+
+    let jury_config = &mut ctx.accounts.jury_config;
+    jury_config.min_juror_reputation = min_juror_reputation;
+    jury_config.min_juror_stake = min_juror_stake;
+    jury_config.jury_size = jury_size;
+    jury_config.commit_period = commit_period;
+    jury_config.reveal_period = reveal_period;
+    jury_config.juror_reward = juror_reward;
+    jury_config.non_reveal_penalty = non_reveal_penalty;
+    jury_config.juror_exit_delay = juror_exit_delay;
+    jury_config.appeal_window = appeal_window;
+    jury_config.appeal_bond = appeal_bond;
+    jury_config.dispute_bond = dispute_bond;
+    jury_config.summon_period = summon_period;
+    jury_config.vault = ctx.accounts.juror_vault.key();
+    jury_config.jurors = [Pubkey::default(); crate::state::MAX_JURORS];
+    jury_config.juror_count = 0;
+    jury_config.bump = ctx.bumps.jury_config;
+
+    msg!("Jury config initialized: {} jurors per dispute", jury_size);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RegisterJuror<'info> {
+    #[account(mut)]
+    pub agent: Signer<'info>,
+
+    #[account(
+        seeds = [b"agent", agent.key().as_ref()],
+        bump = agent_profile.bump,
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        init,
+        payer = agent,
+        space = Juror::LEN,
+        seeds = [b"juror", agent.key().as_ref()],
+        bump
+    )]
+    pub juror: Account<'info, Juror>,
+
+    #[account(
+        mut,
+        seeds = [b"jury_config"],
+        bump = jury_config.bump,
+    )]
+    pub jury_config: Account<'info, JuryConfig>,
+
+    #[account(
+        mut,
+        address = jury_config.vault,
+    )]
+    pub juror_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = juror_vault.mint,
+        token::authority = agent,
+    )]
+    pub agent_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub token_program: Program<'info, token::Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Stake tokens to join the juror pool
+pub fn register_juror(ctx: Context<RegisterJuror>, stake: u64) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE)?;
+
+    let jury_config = &mut ctx.accounts.jury_config;
+    let profile = &ctx.accounts.agent_profile;
+    require!(profile.is_active, ReputationError::AgentInactive);
+    require!(
+        profile.reputation_score >= jury_config.min_juror_reputation,
+        ReputationError::InsufficientReputation
+    );
+    require!(stake >= jury_config.min_juror_stake, ReputationError::InvalidVouchAmount);
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.agent_token_account.to_account_info(),
+                to: ctx.accounts.juror_vault.to_account_info(),
+                authority: ctx.accounts.agent.to_account_info(),
+            },
+        ),
+        stake,
+    )?;
+
+    jury_config.add_juror(ctx.accounts.agent.key())?;
+
+    let juror = &mut ctx.accounts.juror;
+    juror.agent = ctx.accounts.agent.key();
+    juror.stake = stake;
+    juror.registered_at = Clock::get()?.unix_timestamp;
+    juror.exit_requested_at = 0;
+    juror.cases_served = 0;
+    juror.active_cases = 0;
+    juror.bump = ctx.bumps.juror;
+
+    msg!("Juror registered: {} with stake {}", juror.agent, stake);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RequestJurorExit<'info> {
+    pub agent: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"juror", agent.key().as_ref()],
+        bump = juror.bump,
+        has_one = agent @ ReputationError::Unauthorized,
+    )]
+    pub juror: Account<'info, Juror>,
+
+    #[account(
+        mut,
+        seeds = [b"jury_config"],
+        bump = jury_config.bump,
+    )]
+    pub jury_config: Account<'info, JuryConfig>,
+}
+
+/// Leave the pool. The stake can be withdrawn after `juror_exit_delay`.
+pub fn request_juror_exit(ctx: Context<RequestJurorExit>) -> Result<()> {
+    let juror = &mut ctx.accounts.juror;
+    require!(juror.exit_requested_at == 0, ReputationError::JurorExitPending);
+
+    ctx.accounts.jury_config.remove_juror(&juror.agent);
+    juror.exit_requested_at = Clock::get()?.unix_timestamp;
+
+    msg!("Juror {} left the pool", juror.agent);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawJurorStake<'info> {
+    #[account(mut)]
+    pub agent: Signer<'info>,
+
+    #[account(
+        mut,
+        close = agent,
+        seeds = [b"juror", agent.key().as_ref()],
+        bump = juror.bump,
+        has_one = agent @ ReputationError::Unauthorized,
+    )]
+    pub juror: Account<'info, Juror>,
+
+    #[account(
+        seeds = [b"jury_config"],
+        bump = jury_config.bump,
+    )]
+    pub jury_config: Account<'info, JuryConfig>,
+
+    #[account(
+        mut,
+        address = jury_config.vault,
+    )]
+    pub juror_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = juror_vault.mint,
+        token::authority = agent,
+    )]
+    pub agent_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub token_program: Program<'info, token::Token>,
+}
+
+pub fn withdraw_juror_stake(ctx: Context<WithdrawJurorStake>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_WITHDRAWALS)?;
+
+    let juror = &ctx.accounts.juror;
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        juror.exit_requested_at > 0
+            && now >= juror.exit_requested_at.saturating_add(ctx.accounts.jury_config.juror_exit_delay),
+        ReputationError::JurorExitPending
+    );
+    require!(juror.active_cases == 0, ReputationError::JurorOnActiveCase);
+
+    let amount = juror.stake.min(ctx.accounts.juror_vault.amount);
+    let seeds: &[&[u8]] = &[b"jury_config", &[ctx.accounts.jury_config.bump]];
+    let signer = &[seeds];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.juror_vault.to_account_info(),
+                to: ctx.accounts.agent_token_account.to_account_info(),
+                authority: ctx.accounts.jury_config.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+    )?;
+
+    msg!("Juror stake withdrawn: {} tokens to {}", amount, juror.agent);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OpenVouchDispute<'info> {
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+
+    /// The challenger's negative vouch against the agent
+    #[account(
+        seeds = [b"vouch", challenger.key().as_ref(), vouch_record.vouched_for.as_ref()],
+        bump = vouch_record.bump,
+        constraint = !vouch_record.is_positive @ ReputationError::InvalidVouchRecord,
+    )]
+    pub vouch_record: Account<'info, VouchRecord>,
+
+    #[account(
+        init,
+        payer = challenger,
+        space = Dispute::LEN,
+        seeds = [b"dispute", vouch_record.key().as_ref()],
+        bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        seeds = [b"agent", vouch_record.vouched_for.as_ref()],
+        bump = agent_profile.bump,
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        seeds = [b"jury_config"],
+        bump = jury_config.bump,
+    )]
+    pub jury_config: Account<'info, JuryConfig>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// Holds the challenger's dispute bond until the verdict is final
+    #[account(
+        init,
+        payer = challenger,
+        seeds = [b"dispute_escrow", dispute.key().as_ref()],
+        bump,
+        token::mint = reputation_mint,
+        token::authority = dispute,
+    )]
+    pub dispute_escrow: Account<'info, TokenAccount>,
+
+    #[account(address = config.reputation_mint)]
+    pub reputation_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = reputation_mint,
+        token::authority = challenger,
+    )]
+    pub challenger_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, token::Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Escalate a negative vouch into a dispute for a jury to decide. The
+/// challenger posts `dispute_bond`, which is lost if the agent wins.
+pub fn open_vouch_dispute(ctx: Context<OpenVouchDispute>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let vouch = &ctx.accounts.vouch_record;
+    let jury_config = &ctx.accounts.jury_config;
+    require!(!vouch.is_expired(now), ReputationError::VouchExpired);
+
+    let bond = jury_config.dispute_bond;
+    if bond > 0 {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.challenger_token_account.to_account_info(),
+                    to: ctx.accounts.dispute_escrow.to_account_info(),
+                    authority: ctx.accounts.challenger.to_account_info(),
+                },
+            ),
+            bond,
+        )?;
+    }
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.subject = vouch.key();
+    dispute.kind = DisputeKind::Vouch;
+    dispute.claimant = vouch.voucher;
+    dispute.agent = vouch.vouched_for;
+    dispute.opened_at = now;
+    dispute.round = 0;
+    dispute.resolved = false;
+    dispute.agent_won = false;
+    dispute.appealable_until = 0;
+    dispute.appeal_count = 0;
+    dispute.appellant = Pubkey::default();
+    dispute.appeal_bond = 0;
+    dispute.appealed_verdict = false;
+    dispute.closed = false;
+    dispute.claimant_bond = bond;
+    dispute.summon_deadline = now.saturating_add(jury_config.summon_period);
+    dispute.seed_slot = Clock::get()?.slot.saturating_add(JURY_SEED_DELAY_SLOTS);
+    dispute.snapshot_pool(jury_config);
+    // A dispute no jury can hear would lock the agent's vouches for nothing
+    require!(
+        dispute.pool().len() >= jury_config.jury_size_for_round(1),
+        ReputationError::NotEnoughJurors
+    );
+    dispute.bump = ctx.bumps.dispute;
+
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.open_disputes = profile.open_disputes.saturating_add(1);
+
+    msg!("Vouch dispute opened: {} challenges {} | Bond: {}", dispute.claimant, dispute.agent, bond);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SummonJury<'info> {
+    /// Anyone can seat the jury; pays for the jury account
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"dispute", dispute.subject.as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        init,
+        payer = caller,
+        space = Jury::LEN,
+        seeds = [b"jury", dispute.key().as_ref(), &[dispute.round + 1]],
+        bump
+    )]
+    pub jury: Account<'info, Jury>,
+
+    #[account(
+        seeds = [b"jury_config"],
+        bump = jury_config.bump,
+    )]
+    pub jury_config: Account<'info, JuryConfig>,
+
+    /// CHECK: SlotHashes sysvar, read raw since it's too large to deserialize
+    #[account(address = slot_hashes::ID)]
+    pub slot_hashes: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Seat a jury drawn from the pool with a seed taken from the dispute's seed
+/// slot hash, from the pool snapshotted when the round opened. Called once when
+/// the dispute opens and once after each appeal. Remaining accounts are the
+/// Juror PDAs in draw order, up to the last juror seated.
+pub fn summon_jury<'info>(ctx: Context<'_, '_, 'info, 'info, SummonJury<'info>>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE)?;
+
+    let dispute = &ctx.accounts.dispute;
+    require!(!dispute.resolved, ReputationError::DisputeAlreadyResolved);
+    require!(dispute.round == dispute.appeal_count, ReputationError::JuryNotReady);
+    require!(Clock::get()?.unix_timestamp < dispute.summon_deadline, ReputationError::SummonPeriodClosed);
+
+    let jury_config = &ctx.accounts.jury_config;
+    let jury_size = jury_config.jury_size_for_round(dispute.round + 1);
+    let seed = jury_seed(&ctx.accounts.slot_hashes, dispute.seed_slot, &dispute.key(), dispute.round + 1)?;
+
+    // Walk the draw order until the jury is full. Drawn jurors who have since
+    // withdrawn or been penalised below the minimum stake are passed over, so
+    // their accounts must be passed too.
+    let mut jurors = Vec::with_capacity(jury_size);
+    let mut accounts = ctx.remaining_accounts.iter();
+    for agent in draw_jurors(dispute.pool(), &seed) {
+        if jurors.len() == jury_size {
+            break;
+        }
+        let info = accounts.next().ok_or(ReputationError::NotEnoughJurors)?;
+        let (expected, _) = Pubkey::find_program_address(&[b"juror", agent.as_ref()], ctx.program_id);
+        require_keys_eq!(expected, *info.key, ReputationError::NotAJuror);
+        if info.data_is_empty() {
+            continue;
+        }
+
+        let mut juror: Account<Juror> = Account::try_from(info)?;
+        if juror.stake < jury_config.min_juror_stake {
+            continue;
+        }
+        // Held until finalize_jury so a seated juror can't withdraw mid-case
+        juror.active_cases = juror.active_cases.saturating_add(1);
+        juror.exit(ctx.program_id)?;
+        jurors.push(agent);
+    }
+    require!(jurors.len() == jury_size, ReputationError::NotEnoughJurors);
+
+    seat_jury(
+        &mut ctx.accounts.jury,
+        &ctx.accounts.jury_config,
+        dispute.key(),
+        dispute.round + 1,
+        seed,
+        &jurors,
+        ctx.bumps.jury,
+    )?;
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.round += 1;
+
+    msg!("Jury seated for dispute {}: {} jurors", dispute.key(), jurors.len());
+    Ok(())
+}
+
+/// Mix the hash of the dispute's seed slot with the dispute and round
+pub(crate) fn jury_seed(slot_hashes: &AccountInfo, seed_slot: u64, dispute: &Pubkey, round: u8) -> Result<[u8; 32]> {
+    let data = slot_hashes.try_borrow_data()?;
+    let seed_hash = seed_slot_hash(&data, seed_slot)?;
+    Ok(hashv(&[seed_hash, dispute.as_ref(), &[round]]).to_bytes())
+}
+
+/// Hash of `seed_slot` in raw SlotHashes data. If that slot was skipped, the
+/// first slot after it stands in.
+pub(crate) fn seed_slot_hash(data: &[u8], seed_slot: u64) -> Result<&[u8]> {
+    // Layout: u64 entry count, then (u64 slot, [u8; 32] hash) entries, newest first
+    require!(data.len() >= 8, ReputationError::SeedSlotNotReached);
+    let count = u64::from_le_bytes(data[..8].try_into().unwrap()) as usize;
+
+    let mut seed_hash = None;
+    let mut reaches_back = false;
+    for entry in data[8..].chunks_exact(40).take(count) {
+        let slot = u64::from_le_bytes(entry[..8].try_into().unwrap());
+        if slot < seed_slot {
+            reaches_back = true;
+            break;
+        }
+        seed_hash = Some(&entry[8..40]);
+        if slot == seed_slot {
+            reaches_back = true;
+            break;
+        }
+    }
+    let seed_hash = seed_hash.ok_or(ReputationError::SeedSlotNotReached)?;
+    // Without an older entry, earlier slots at or after seed_slot may have been dropped
+    require!(reaches_back, ReputationError::SeedSlotExpired);
+    Ok(seed_hash)
+}
+
+/// The whole pool in the order `seed` draws it; the jury is taken from the front
+pub(crate) fn draw_jurors(pool: &[Pubkey], seed: &[u8; 32]) -> Vec<Pubkey> {
+    let mut candidates = pool.to_vec();
+    let mut order = Vec::with_capacity(candidates.len());
+    for i in 0..pool.len() {
+        let draw = hashv(&[seed, &(i as u64).to_le_bytes()]).to_bytes();
+        let index = u64::from_le_bytes(draw[..8].try_into().unwrap()) % candidates.len() as u64;
+        order.push(candidates.swap_remove(index as usize));
+    }
+    order
+}
+
+pub(crate) fn seat_jury(
+    jury: &mut Jury,
+    jury_config: &JuryConfig,
+    dispute: Pubkey,
+    round: u8,
+    seed: [u8; 32],
+    jurors: &[Pubkey],
+    bump: u8,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    jury.dispute = dispute;
+    jury.round = round;
+    jury.seed = seed;
+    jury.jurors = [Pubkey::default(); MAX_JURY_SIZE];
+    jury.jurors[..jurors.len()].copy_from_slice(jurors);
+    jury.juror_count = jurors.len() as u8;
+    jury.commitments = [[0; 32]; MAX_JURY_SIZE];
+    jury.votes = [VOTE_NONE; MAX_JURY_SIZE];
+    jury.commit_deadline = now.saturating_add(jury_config.commit_period);
+    jury.reveal_deadline = jury.commit_deadline.saturating_add(jury_config.reveal_period);
+    jury.finalized = false;
+    jury.bump = bump;
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CommitJuryVote<'info> {
+    pub juror: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"jury", jury.dispute.as_ref(), &[jury.round]],
+        bump = jury.bump,
+    )]
+    pub jury: Account<'info, Jury>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+/// Commit `jury_vote_commitment(jury, juror, vote, salt)` during the commit period
+pub fn commit_jury_vote(ctx: Context<CommitJuryVote>, commitment: [u8; 32]) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE)?;
+
+    let jury = &mut ctx.accounts.jury;
+    let seat = jury.seat_of(&ctx.accounts.juror.key()).ok_or(ReputationError::NotAJuror)?;
+
+    require!(Clock::get()?.unix_timestamp < jury.commit_deadline, ReputationError::CommitPeriodClosed);
+    require!(jury.commitments[seat] == [0; 32], ReputationError::AlreadyCommitted);
+
+    jury.commitments[seat] = commitment;
+
+    msg!("Jury vote committed by {}", ctx.accounts.juror.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RevealJuryVote<'info> {
+    pub juror: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"jury", jury.dispute.as_ref(), &[jury.round]],
+        bump = jury.bump,
+    )]
+    pub jury: Account<'info, Jury>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+pub fn reveal_jury_vote(ctx: Context<RevealJuryVote>, agent_wins: bool, salt: [u8; 32]) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE)?;
+
+    let jury_key = ctx.accounts.jury.key();
+    let juror = ctx.accounts.juror.key();
+    let jury = &mut ctx.accounts.jury;
+    let seat = jury.seat_of(&juror).ok_or(ReputationError::NotAJuror)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(
+        now >= jury.commit_deadline && now < jury.reveal_deadline,
+        ReputationError::RevealPeriodClosed
+    );
+    require!(jury.votes[seat] == VOTE_NONE, ReputationError::AlreadyVoted);
+
+    let vote = if agent_wins { VOTE_AGENT } else { VOTE_CLAIMANT };
+    require!(
+        jury.commitments[seat] == jury_vote_commitment(&jury_key, &juror, vote, &salt),
+        ReputationError::InvalidReveal
+    );
+
+    jury.votes[seat] = vote;
+
+    msg!("Jury vote revealed by {}", juror);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct FinalizeJury<'info> {
+    /// Anyone can finalize once the reveal period is over
+    pub caller: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"jury", dispute.key().as_ref(), &[jury.round]],
+        bump = jury.bump,
+    )]
+    pub jury: Account<'info, Jury>,
+
+    #[account(
+        mut,
+        seeds = [b"dispute", dispute.subject.as_ref()],
+        bump = dispute.bump,
+        constraint = jury.round == dispute.round @ ReputationError::JuryNotReady,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        seeds = [b"jury_config"],
+        bump = jury_config.bump,
+    )]
+    pub jury_config: Account<'info, JuryConfig>,
+
+    #[account(
+        mut,
+        address = jury_config.vault,
+    )]
+    pub juror_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        address = treasury.vault,
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub token_program: Program<'info, token::Token>,
+}
+
+/// Tally revealed votes and settle juror stakes. The seated jurors' `Juror`
+/// accounts are passed in remaining accounts, in seat order. Ties go to the
+/// agent. The verdict only takes effect once the appeal window has passed.
+pub fn finalize_jury<'info>(ctx: Context<'_, '_, 'info, 'info, FinalizeJury<'info>>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE | PAUSE_WITHDRAWALS)?;
+
+    let jury = &ctx.accounts.jury;
+    require!(!jury.finalized, ReputationError::DisputeAlreadyResolved);
+
+    let now = Clock::get()?.unix_timestamp;
+    let seats = jury.juror_count as usize;
+    let votes = &jury.votes[..seats];
+    let all_revealed = votes.iter().all(|v| *v != VOTE_NONE);
+    require!(
+        all_revealed || now >= jury.reveal_deadline,
+        ReputationError::JuryNotReady
+    );
+    require!(ctx.remaining_accounts.len() == seats, ReputationError::NotAJuror);
+
+    let agent_votes = votes.iter().filter(|v| **v == VOTE_AGENT).count();
+    let claimant_votes = votes.iter().filter(|v| **v == VOTE_CLAIMANT).count();
+    let agent_won = agent_votes >= claimant_votes;
+    let majority = if agent_won { VOTE_AGENT } else { VOTE_CLAIMANT };
+    let majority_count = if agent_won { agent_votes } else { claimant_votes } as u64;
+
+    // Rewards come out of the treasury; split what's there if it runs short
+    let jury_config = &ctx.accounts.jury_config;
+    let reward = ctx.accounts.treasury_vault.amount
+        .checked_div(majority_count)
+        .map_or(0, |share| share.min(jury_config.juror_reward));
+
+    let mut total_reward: u64 = 0;
+    let mut total_penalty: u64 = 0;
+    let mut understaked = Vec::new();
+    for (seat, info) in ctx.remaining_accounts.iter().enumerate() {
+        let (expected, _) = Pubkey::find_program_address(&[b"juror", jury.jurors[seat].as_ref()], ctx.program_id);
+        require_keys_eq!(expected, *info.key, ReputationError::NotAJuror);
+
+        // Seated jurors can't withdraw, but a missing account shouldn't hold up the verdict
+        if info.data_is_empty() {
+            continue;
+        }
+
+        let mut juror: Account<Juror> = Account::try_from(info)?;
+        if votes[seat] == majority {
+            juror.stake = juror.stake.saturating_add(reward);
+            total_reward += reward;
+        } else if votes[seat] == VOTE_NONE {
+            let penalty = jury_config.non_reveal_penalty.min(juror.stake);
+            juror.stake -= penalty;
+            total_penalty += penalty;
+            if juror.stake < jury_config.min_juror_stake {
+                understaked.push(juror.agent);
+            }
+        }
+        juror.cases_served = juror.cases_served.saturating_add(1);
+        juror.active_cases = juror.active_cases.saturating_sub(1);
+        juror.exit(ctx.program_id)?;
+    }
+
+    if total_reward > 0 {
+        let seeds: &[&[u8]] = &[b"treasury", &[ctx.accounts.treasury.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.treasury_vault.to_account_info(),
+                    to: ctx.accounts.juror_vault.to_account_info(),
+                    authority: ctx.accounts.treasury.to_account_info(),
+                },
+                &[seeds],
+            ),
+            total_reward,
+        )?;
+    }
+    if total_penalty > 0 {
+        let seeds: &[&[u8]] = &[b"jury_config", &[jury_config.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.juror_vault.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: jury_config.to_account_info(),
+                },
+                &[seeds],
+            ),
+            total_penalty,
+        )?;
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.total_slashed = treasury.total_slashed.saturating_add(total_penalty);
+    }
+
+    // Penalised below the minimum stake: no longer drawn for new disputes
+    let jury_config = &mut ctx.accounts.jury_config;
+    for agent in &understaked {
+        jury_config.remove_juror(agent);
+    }
+
+    ctx.accounts.jury.finalized = true;
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.resolved = true;
+    dispute.agent_won = agent_won;
+    dispute.appealable_until = now.saturating_add(jury_config.appeal_window);
+
+    msg!("Jury finalized for dispute {}: {} for agent, {} for claimant | Agent won: {}",
+        dispute.key(), agent_votes, claimant_votes, agent_won);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AppealDispute<'info> {
+    /// The losing party
+    #[account(mut)]
+    pub appellant: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"dispute", dispute.subject.as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        seeds = [b"jury_config"],
+        bump = jury_config.bump,
+    )]
+    pub jury_config: Account<'info, JuryConfig>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// Holds appeal bonds until each appeal round is settled
+    #[account(
+        init_if_needed,
+        payer = appellant,
+        seeds = [b"appeal_escrow", dispute.key().as_ref()],
+        bump,
+        token::mint = reputation_mint,
+        token::authority = dispute,
+    )]
+    pub appeal_escrow: Account<'info, TokenAccount>,
+
+    #[account(address = config.reputation_mint)]
+    pub reputation_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = reputation_mint,
+        token::authority = appellant,
+    )]
+    pub appellant_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, token::Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Appeal a verdict within its appeal window. The bond doubles with every
+/// appeal and the next jury is more than twice the size of the last.
+pub fn appeal_dispute(ctx: Context<AppealDispute>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE)?;
+
+    let dispute = &ctx.accounts.dispute;
+    let jury_config = &ctx.accounts.jury_config;
+    require!(dispute.resolved && !dispute.closed, ReputationError::DisputeNotResolved);
+    require!(Clock::get()?.unix_timestamp < dispute.appealable_until, ReputationError::AppealWindowClosed);
+    require!(dispute.appeal_bond == 0, ReputationError::AppealBondUnsettled);
+
+    let loser = if dispute.agent_won { dispute.claimant } else { dispute.agent };
+    require_keys_eq!(ctx.accounts.appellant.key(), loser, ReputationError::Unauthorized);
+    let next_size = jury_config.jury_size_for_round(dispute.round + 1);
+    require!(next_size <= MAX_JURY_SIZE, ReputationError::AppealLimitReached);
+
+    let bond = jury_config.appeal_bond_for(dispute.appeal_count);
+    if bond > 0 {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.appellant_token_account.to_account_info(),
+                    to: ctx.accounts.appeal_escrow.to_account_info(),
+                    authority: ctx.accounts.appellant.to_account_info(),
+                },
+            ),
+            bond,
+        )?;
+    }
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.appealed_verdict = dispute.agent_won;
+    dispute.resolved = false;
+    dispute.appeal_count += 1;
+    dispute.appellant = loser;
+    dispute.appeal_bond = bond;
+    let clock = Clock::get()?;
+    dispute.summon_deadline = clock.unix_timestamp.saturating_add(jury_config.summon_period);
+    dispute.seed_slot = clock.slot.saturating_add(JURY_SEED_DELAY_SLOTS);
+    dispute.snapshot_pool(jury_config);
+    require!(dispute.pool().len() >= next_size, ReputationError::NotEnoughJurors);
+
+    msg!("Dispute {} appealed by {} | Bond: {} | Appeal #{}",
+        dispute.key(), loser, bond, dispute.appeal_count);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleAppeal<'info> {
+    #[account(
+        mut,
+        seeds = [b"dispute", dispute.subject.as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        seeds = [b"appeal_escrow", dispute.key().as_ref()],
+        bump,
+    )]
+    pub appeal_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = appeal_escrow.mint,
+        token::authority = dispute.appellant,
+    )]
+    pub appellant_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        address = treasury.vault,
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub token_program: Program<'info, token::Token>,
+}
+
+/// Once the appeal jury has ruled, return the bond if the verdict was
+/// overturned, otherwise forfeit it to the treasury
+pub fn settle_appeal(ctx: Context<SettleAppeal>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE | PAUSE_WITHDRAWALS)?;
+
+    let dispute = &ctx.accounts.dispute;
+    require!(dispute.appeal_bond > 0, ReputationError::NoPendingAppeal);
+    require!(
+        dispute.resolved && dispute.round == dispute.appeal_count,
+        ReputationError::DisputeNotResolved
+    );
+
+    let overturned = dispute.agent_won != dispute.appealed_verdict;
+    let to = if overturned {
+        ctx.accounts.appellant_token_account.to_account_info()
+    } else {
+        ctx.accounts.treasury_vault.to_account_info()
+    };
+    let amount = dispute.appeal_bond.min(ctx.accounts.appeal_escrow.amount);
+
+    let seeds: &[&[u8]] = &[b"dispute", dispute.subject.as_ref(), &[dispute.bump]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.appeal_escrow.to_account_info(),
+                to,
+                authority: dispute.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )?;
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.appeal_bond = 0;
+
+    msg!("Appeal settled for dispute {} | Overturned: {} | Bond: {}", dispute.key(), overturned, amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SettleDisputeBond<'info> {
+    #[account(
+        mut,
+        seeds = [b"dispute", dispute.subject.as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        seeds = [b"dispute_escrow", dispute.key().as_ref()],
+        bump,
+    )]
+    pub dispute_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = dispute_escrow.mint,
+        token::authority = dispute.claimant,
+    )]
+    pub claimant_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        address = treasury.vault,
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub token_program: Program<'info, token::Token>,
+}
+
+/// Once the verdict is final, return the challenger's dispute bond if they
+/// won, otherwise forfeit it to the treasury
+pub fn settle_dispute_bond(ctx: Context<SettleDisputeBond>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE | PAUSE_WITHDRAWALS)?;
+
+    let dispute = &ctx.accounts.dispute;
+    require!(dispute.claimant_bond > 0, ReputationError::NoDisputeBond);
+    require!(dispute.is_final(Clock::get()?.unix_timestamp), ReputationError::AppealWindowOpen);
+
+    let to = if dispute.agent_won {
+        ctx.accounts.treasury_vault.to_account_info()
+    } else {
+        ctx.accounts.claimant_token_account.to_account_info()
+    };
+    let amount = dispute.claimant_bond.min(ctx.accounts.dispute_escrow.amount);
+
+    let seeds: &[&[u8]] = &[b"dispute", dispute.subject.as_ref(), &[dispute.bump]];
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.dispute_escrow.to_account_info(),
+                to,
+                authority: dispute.to_account_info(),
+            },
+            &[seeds],
+        ),
+        amount,
+    )?;
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.claimant_bond = 0;
+
+    msg!("Dispute bond settled for {} | Agent won: {} | Bond: {}", dispute.key(), dispute.agent_won, amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct LapseAppeal<'info> {
+    #[account(
+        mut,
+        seeds = [b"dispute", dispute.subject.as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        seeds = [b"appeal_escrow", dispute.key().as_ref()],
+        bump,
+    )]
+    pub appeal_escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        address = treasury.vault,
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub token_program: Program<'info, token::Token>,
+}
+
+/// Drop an appeal nobody seated a jury for by `summon_deadline`. Anyone can
+/// call it; the appealed verdict stands and the appeal bond is forfeited.
+pub fn lapse_appeal(ctx: Context<LapseAppeal>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE | PAUSE_WITHDRAWALS)?;
+
+    let dispute = &ctx.accounts.dispute;
+    let now = Clock::get()?.unix_timestamp;
+    require!(!dispute.closed, ReputationError::DisputeAlreadyResolved);
+    require!(
+        !dispute.resolved && dispute.round < dispute.appeal_count,
+        ReputationError::NoPendingAppeal
+    );
+    require!(now >= dispute.summon_deadline, ReputationError::SummonPeriodOpen);
+
+    let amount = dispute.appeal_bond.min(ctx.accounts.appeal_escrow.amount);
+    if amount > 0 {
+        let seeds: &[&[u8]] = &[b"dispute", dispute.subject.as_ref(), &[dispute.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.appeal_escrow.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: dispute.to_account_info(),
+                },
+                &[seeds],
+            ),
+            amount,
+        )?;
+    }
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.agent_won = dispute.appealed_verdict;
+    dispute.resolved = true;
+    dispute.appealable_until = now;
+    dispute.appeal_count -= 1;
+    dispute.appeal_bond = 0;
+
+    msg!("Appeal lapsed for dispute {} | Agent won: {} | Bond forfeited: {}",
+        dispute.key(), dispute.agent_won, amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseDispute<'info> {
+    #[account(
+        mut,
+        seeds = [b"dispute", dispute.subject.as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        seeds = [b"agent", dispute.agent.as_ref()],
+        bump = agent_profile.bump,
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        seeds = [b"jury_config"],
+        bump = jury_config.bump,
+    )]
+    pub jury_config: Account<'info, JuryConfig>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+/// Unlock the agent's vouch stake once the verdict is final. Waits a further
+/// `appeal_window` after the verdict takes effect so slashes can land first.
+pub fn close_dispute(ctx: Context<CloseDispute>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE)?;
+
+    let dispute = &ctx.accounts.dispute;
+    let now = Clock::get()?.unix_timestamp;
+    require!(!dispute.closed, ReputationError::DisputeAlreadyResolved);
+    require!(dispute.is_final(now), ReputationError::AppealWindowOpen);
+    require!(dispute.appeal_bond == 0, ReputationError::AppealBondUnsettled);
+    require!(dispute.claimant_bond == 0, ReputationError::DisputeBondUnsettled);
+    require!(
+        now >= dispute.appealable_until.saturating_add(ctx.accounts.jury_config.appeal_window),
+        ReputationError::AppealWindowOpen
+    );
+
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.open_disputes = profile.open_disputes.saturating_sub(1);
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.closed = true;
+
+    msg!("Dispute {} closed | Agent won: {}", dispute.key(), dispute.agent_won);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct VoidDispute<'info> {
+    #[account(
+        mut,
+        seeds = [b"dispute", dispute.subject.as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        seeds = [b"agent", dispute.agent.as_ref()],
+        bump = agent_profile.bump,
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+
+    /// Required when the challenger posted a dispute bond
+    #[account(
+        mut,
+        seeds = [b"dispute_escrow", dispute.key().as_ref()],
+        bump,
+    )]
+    pub dispute_escrow: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        mut,
+        token::authority = dispute.claimant,
+    )]
+    pub claimant_token_account: Option<Account<'info, TokenAccount>>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub token_program: Program<'info, token::Token>,
+}
+
+/// Close a dispute nobody seated a jury for by `summon_deadline`. Anyone can
+/// call it; the agent's vouches unlock and the challenger's bond is returned.
+pub fn void_dispute(ctx: Context<VoidDispute>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_GOVERNANCE | PAUSE_WITHDRAWALS)?;
+
+    let dispute = &ctx.accounts.dispute;
+    require!(!dispute.closed, ReputationError::DisputeAlreadyResolved);
+    require!(dispute.round == 0, ReputationError::JuryAlreadySummoned);
+    require!(Clock::get()?.unix_timestamp >= dispute.summon_deadline, ReputationError::SummonPeriodOpen);
+
+    let bond = dispute.claimant_bond;
+    if bond > 0 {
+        let escrow = ctx.accounts.dispute_escrow.as_ref().ok_or(ReputationError::DisputeBondUnsettled)?;
+        let claimant_account = ctx.accounts.claimant_token_account.as_ref()
+            .ok_or(ReputationError::DisputeBondUnsettled)?;
+
+        let seeds: &[&[u8]] = &[b"dispute", dispute.subject.as_ref(), &[dispute.bump]];
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: escrow.to_account_info(),
+                    to: claimant_account.to_account_info(),
+                    authority: dispute.to_account_info(),
+                },
+                &[seeds],
+            ),
+            bond.min(escrow.amount),
+        )?;
+    }
+
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.open_disputes = profile.open_disputes.saturating_sub(1);
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.closed = true;
+    dispute.claimant_bond = 0;
+
+    msg!("Dispute {} voided: no jury seated in time", dispute.key());
+    Ok(())
+}
@@ -1,13 +1,41 @@
 pub mod initialize;
+pub mod config;
 pub mod register_agent;
 pub mod complete_task;
 pub mod vouch;
+pub mod adjust_vouch;
+pub mod expiry;
 pub mod decay;
 pub mod query;
+pub mod snapshot;
+pub mod treasury;
+pub mod rewards;
+pub mod audit;
+pub mod domain;
+pub mod task_bounty;
+pub mod rating;
+pub mod client_key;
+pub mod governance;
+pub mod jury;
+pub mod tier;
 
 pub use initialize::*;
+pub use config::*;
 pub use register_agent::*;
 pub use complete_task::*;
 pub use vouch::*;
+pub use adjust_vouch::*;
+pub use expiry::*;
 pub use decay::*;
 pub use query::*;
+pub use snapshot::*;
+pub use treasury::*;
+pub use rewards::*;
+pub use audit::*;
+pub use domain::*;
+pub use task_bounty::*;
+pub use rating::*;
+pub use client_key::*;
+pub use governance::*;
+pub use jury::*;
+pub use tier::*;
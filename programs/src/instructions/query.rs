@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
-use crate::state::AgentProfile;
+use crate::state::{AgentProfile, DomainReputation, DomainScore, ProtocolConfig, ReputationView, calculate_decay_days, calculate_profile_decay};
+use crate::errors::ReputationError;
 
 #[derive(Accounts)]
 pub struct GetReputation<'info> {
@@ -11,8 +12,48 @@ pub struct GetReputation<'info> {
     
     /// CHECK: Just the pubkey
     pub agent: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+/// Domain scores are read from `DomainReputation` accounts passed as remaining
+/// accounts. Every score has its pending decay applied, so the view doesn't
+/// depend on when decay was last cranked.
+pub fn get_reputation<'info>(ctx: Context<'_, '_, 'info, 'info, GetReputation<'info>>) -> Result<ReputationView> {
+    let agent = ctx.accounts.agent.key();
+    let config = &ctx.accounts.config;
+    let now = Clock::get()?.unix_timestamp;
+    let mut domains = Vec::with_capacity(ctx.remaining_accounts.len());
+    
+    for info in ctx.remaining_accounts.iter() {
+        let domain: Account<DomainReputation> = Account::try_from(info)
+            .map_err(|_| error!(ReputationError::InvalidDomainAccount))?;
+        require_keys_eq!(domain.agent, agent, ReputationError::InvalidDomainAccount);
+        
+        domains.push(DomainScore {
+            category_id: domain.category_id,
+            score: domain.pending_decay(now, config).1,
+        });
+    }
+    
+    let profile = &ctx.accounts.agent_profile;
+    let days = calculate_decay_days(profile.last_activity_timestamp, now, config.decay_grace_days);
+    Ok(ReputationView {
+        score: calculate_profile_decay(profile.reputation_score, days, profile.total_tasks_completed, config),
+        domains,
+    })
 }
 
-pub fn get_reputation(ctx: Context<GetReputation>) -> Result<u64> {
-    Ok(ctx.accounts.agent_profile.reputation_score)
+/// Score the agent held at `timestamp`, from its checkpoint history. Use a
+/// past timestamp (e.g. a proposal's creation) so the result can't be
+/// inflated within the same transaction.
+pub fn get_reputation_at(ctx: Context<GetReputation>, timestamp: i64) -> Result<u64> {
+    ctx.accounts
+        .agent_profile
+        .reputation_at(timestamp)
+        .ok_or_else(|| error!(ReputationError::HistoryUnavailable))
 }
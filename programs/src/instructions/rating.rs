@@ -0,0 +1,138 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, TokenAccount};
+use crate::state::{AgentProfile, DomainReputation, ProtocolConfig, TaskRecord, calculate_rated_reputation, PAUSE_MINTING};
+use crate::errors::ReputationError;
+use super::complete_task::mint_task_reputation;
+use super::tier::{load_tier_config, update_tier};
+
+#[derive(Accounts)]
+pub struct RateTask<'info> {
+    /// The task's requester, or the config authority for self-reported tasks
+    pub rater: Signer<'info>,
+
+    #[account(
+        mut,
+        constraint = task_record.has_address(&task_record.key(), &crate::ID) @ ReputationError::InvalidTaskRecord,
+    )]
+    pub task_record: Account<'info, TaskRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"agent", task_record.agent.as_ref()],
+        bump = agent_profile.bump,
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+
+    /// CHECK: Tier config PDA, read by `load_tier_config`
+    #[account(
+        seeds = [b"tier_config"],
+        bump,
+    )]
+    pub tier_config: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"domain", task_record.agent.as_ref(), task_record.category_id.to_le_bytes().as_ref()],
+        bump = domain_reputation.bump,
+    )]
+    pub domain_reputation: Account<'info, DomainReputation>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// CHECK: This is the token mint authority PDA
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = config.reputation_mint
+    )]
+    pub reputation_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reputation_mint,
+        associated_token::authority = task_record.agent,
+    )]
+    pub agent_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault"],
+        bump,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, token::Token>,
+}
+
+/// Rate a completed task. Ratings above 3 mint a bonus; ratings below 3 take
+/// reputation back off the agent's scores (tokens already minted stay put).
+pub fn rate_task(ctx: Context<RateTask>, rating: u8, review_hash: Option<[u8; 32]>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_MINTING)?;
+
+    require!((1..=5).contains(&rating), ReputationError::InvalidRating);
+
+    let record = &ctx.accounts.task_record;
+    require!(record.rating == 0, ReputationError::TaskAlreadyRated);
+
+    let rater = ctx.accounts.rater.key();
+    let expected_rater = if record.requester == Pubkey::default() {
+        ctx.accounts.config.authority
+    } else {
+        record.requester
+    };
+    require_keys_eq!(rater, expected_rater, ReputationError::Unauthorized);
+
+    let now = Clock::get()?.unix_timestamp;
+    let earned = record.reputation_earned;
+    let rated = calculate_rated_reputation(earned, rating);
+
+    let profile = &mut ctx.accounts.agent_profile;
+    let domain = &mut ctx.accounts.domain_reputation;
+    if rated > earned {
+        let bonus = rated - earned;
+        let score = profile.reputation_score.saturating_add(bonus);
+        profile.set_reputation_score(score, now);
+        domain.score = domain.score.saturating_add(bonus);
+
+        mint_task_reputation(
+            &mut ctx.accounts.agent_profile,
+            &mut ctx.accounts.config,
+            &ctx.accounts.token_program,
+            &ctx.accounts.reputation_mint,
+            &ctx.accounts.agent_token_account,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.mint_authority,
+            ctx.bumps.mint_authority,
+            bonus,
+            now,
+        )?;
+    } else {
+        let penalty = earned - rated;
+        let score = profile.reputation_score.saturating_sub(penalty);
+        profile.set_reputation_score(score, now);
+        domain.score = domain.score.saturating_sub(penalty);
+    }
+
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.rating_count = profile.rating_count.saturating_add(1);
+    profile.rating_total = profile.rating_total.saturating_add(rating as u64);
+    update_tier(profile, load_tier_config(&ctx.accounts.tier_config)?.as_ref(), now);
+
+    let record = &mut ctx.accounts.task_record;
+    record.rating = rating;
+    record.review_hash = review_hash.unwrap_or([0; 32]);
+    record.reputation_earned = rated;
+
+    msg!("Task rated: {} | {} stars | Reputation {} -> {}", record.task_id, rating, earned, rated);
+    Ok(())
+}
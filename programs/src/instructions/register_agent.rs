@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
-use crate::state::AgentProfile;
+use anchor_lang::system_program;
+use crate::state::{AgentProfile, ProtocolConfig, RegistrationBond, Treasury, PAUSE_MINTING, PAUSE_WITHDRAWALS};
 use crate::errors::ReputationError;
 
 #[derive(Accounts)]
@@ -17,10 +18,34 @@ pub struct RegisterAgent<'info> {
     )]
     pub agent_profile: Account<'info, AgentProfile>,
     
+    #[account(
+        init,
+        payer = owner,
+        space = RegistrationBond::LEN,
+        seeds = [b"bond", owner.key().as_ref()],
+        bump
+    )]
+    pub registration_bond: Account<'info, RegistrationBond>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    
     pub system_program: Program<'info, System>,
 }
 
 pub fn register_agent(ctx: Context<RegisterAgent>, agent_name: String) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_MINTING)?;
+    
     require!(agent_name.len() <= 50, ReputationError::NameTooLong);
     
     let profile = &mut ctx.accounts.agent_profile;
@@ -28,15 +53,227 @@ pub fn register_agent(ctx: Context<RegisterAgent>, agent_name: String) -> Result
     
     profile.owner = ctx.accounts.owner.key();
     profile.name = agent_name;
-    profile.reputation_score = 0;
     profile.total_tasks_completed = 0;
     profile.last_activity_timestamp = clock.unix_timestamp;
     profile.is_active = true;
     profile.positive_vouches = 0;
     profile.negative_vouches = 0;
     profile.staked_amount = 0;
+    profile.total_vouch_stake = 0;
+    profile.reward_per_share = 0;
+    profile.vouch_ramps = Default::default();
+    profile.rating_count = 0;
+    profile.rating_total = 0;
+    profile.mint_epoch = 0;
+    profile.epoch_minted = 0;
+    profile.open_disputes = 0;
+    profile.tier = 0;
+    profile.checkpoints = Default::default();
+    profile.checkpoint_head = 0;
+    profile.checkpoint_count = 0;
+    profile.set_reputation_score(0, clock.unix_timestamp);
     profile.bump = ctx.bumps.agent_profile;
     
+    // Bond is escrowed in the agent's bond PDA until deregister
+    let bond_amount = ctx.accounts.config.registration_bond;
+    if bond_amount > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.registration_bond.to_account_info(),
+                },
+            ),
+            bond_amount,
+        )?;
+    }
+    
+    let bond = &mut ctx.accounts.registration_bond;
+    bond.agent = ctx.accounts.owner.key();
+    bond.amount = bond_amount;
+    bond.deposited_at = clock.unix_timestamp;
+    bond.bump = ctx.bumps.registration_bond;
+    
+    // Registration fee goes straight to the treasury in SOL
+    let fee = ctx.accounts.config.registration_fee;
+    if fee > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.owner.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            ),
+            fee,
+        )?;
+        
+        let treasury = &mut ctx.accounts.treasury;
+        treasury.total_sol_fees = treasury.total_sol_fees.saturating_add(fee);
+    }
+    
     msg!("Agent registered: {}", profile.name);
     Ok(())
 }
+
+#[derive(Accounts)]
+pub struct DeregisterAgent<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"agent", owner.key().as_ref()],
+        bump = agent_profile.bump,
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"bond", owner.key().as_ref()],
+        bump = registration_bond.bump,
+    )]
+    pub registration_bond: Account<'info, RegistrationBond>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+pub fn deregister_agent(ctx: Context<DeregisterAgent>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_WITHDRAWALS)?;
+    
+    let bond = &ctx.accounts.registration_bond;
+    let clock = Clock::get()?;
+    
+    let tenure = clock.unix_timestamp.saturating_sub(bond.deposited_at);
+    require!(
+        tenure >= ctx.accounts.config.min_registration_tenure,
+        ReputationError::TenureNotReached
+    );
+    require!(
+        ctx.accounts.agent_profile.staked_amount == 0,
+        ReputationError::StakeStillLocked
+    );
+    require!(
+        ctx.accounts.agent_profile.total_vouch_stake == 0,
+        ReputationError::StakeStillLocked
+    );
+    require!(
+        ctx.accounts.agent_profile.open_disputes == 0,
+        ReputationError::AgentUnderDispute
+    );
+    // Re-registering would wipe the record, and vouch records need the profile to close
+    require!(
+        ctx.accounts.agent_profile.positive_vouches == 0 && ctx.accounts.agent_profile.negative_vouches == 0,
+        ReputationError::VouchesOutstanding
+    );
+    
+    // Closing the bond account returns the bond together with its rent
+    msg!("Agent deregistered: {} | Bond refunded: {}", 
+        ctx.accounts.agent_profile.name, bond.amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SlashBond<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    
+    #[account(
+        mut,
+        seeds = [b"agent", agent.key().as_ref()],
+        bump = agent_profile.bump,
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    
+    /// CHECK: Just the pubkey
+    pub agent: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        close = treasury,
+        seeds = [b"bond", agent.key().as_ref()],
+        bump = registration_bond.bump,
+    )]
+    pub registration_bond: Account<'info, RegistrationBond>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+}
+
+pub fn slash_bond(ctx: Context<SlashBond>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_WITHDRAWALS)?;
+    
+    let slashed = ctx.accounts.registration_bond.amount;
+    
+    // Proven sybil: the bond (and its rent) is forfeited and the agent disabled
+    ctx.accounts.agent_profile.is_active = false;
+    ctx.accounts.treasury.total_sol_slashed = 
+        ctx.accounts.treasury.total_sol_slashed.saturating_add(slashed);
+    
+    msg!("Registration bond slashed: {} lamports from {}", slashed, ctx.accounts.agent.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseSlashedAgent<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"agent", owner.key().as_ref()],
+        bump = agent_profile.bump,
+        constraint = !agent_profile.is_active @ ReputationError::BondNotSlashed,
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+    
+    /// CHECK: Must already be closed by `slash_bond`
+    #[account(
+        seeds = [b"bond", owner.key().as_ref()],
+        bump,
+        constraint = registration_bond.data_is_empty() @ ReputationError::BondNotSlashed,
+    )]
+    pub registration_bond: UncheckedAccount<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+pub fn close_slashed_agent(ctx: Context<CloseSlashedAgent>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_WITHDRAWALS)?;
+    
+    let profile = &ctx.accounts.agent_profile;
+    
+    // Same conditions as deregister, minus the bond that is already gone
+    require!(profile.staked_amount == 0, ReputationError::StakeStillLocked);
+    require!(profile.total_vouch_stake == 0, ReputationError::StakeStillLocked);
+    require!(profile.open_disputes == 0, ReputationError::AgentUnderDispute);
+    require!(
+        profile.positive_vouches == 0 && profile.negative_vouches == 0,
+        ReputationError::VouchesOutstanding
+    );
+    
+    msg!("Slashed agent closed: {}", profile.name);
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, TokenAccount, Transfer};
+use crate::state::{AgentProfile, VouchRecord, ProtocolConfig, calculate_pending_reward, PAUSE_WITHDRAWALS};
+use crate::errors::ReputationError;
+
+#[derive(Accounts)]
+pub struct InitializeRewardVault<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// Holds the voucher share of task mints until claimed
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"reward_vault"],
+        bump,
+        token::mint = reputation_mint,
+        token::authority = mint_authority,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: This is the token mint authority PDA
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(address = config.reputation_mint)]
+    pub reputation_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, token::Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn initialize_reward_vault(ctx: Context<InitializeRewardVault>) -> Result<()> {
+    msg!("Reward vault initialized: {}", ctx.accounts.reward_vault.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimVouchRewards<'info> {
+    pub voucher: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vouch", voucher.key().as_ref(), vouch_record.vouched_for.as_ref()],
+        bump = vouch_record.bump,
+    )]
+    pub vouch_record: Account<'info, VouchRecord>,
+
+    #[account(
+        seeds = [b"agent", vouch_record.vouched_for.as_ref()],
+        bump = vouched_for_profile.bump,
+    )]
+    pub vouched_for_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault"],
+        bump,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    /// CHECK: This is the token mint authority PDA
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        associated_token::mint = config.reputation_mint,
+        associated_token::authority = voucher,
+    )]
+    pub voucher_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, token::Token>,
+}
+
+pub fn claim_vouch_rewards(ctx: Context<ClaimVouchRewards>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_WITHDRAWALS)?;
+
+    let reward_per_share = ctx.accounts.vouched_for_profile.reward_per_share;
+    let vouch = &mut ctx.accounts.vouch_record;
+
+    let pending = calculate_pending_reward(vouch.amount, reward_per_share, vouch.reward_debt);
+    require!(pending > 0, ReputationError::NoRewardsToClaim);
+
+    let paid = pay_vouch_reward(
+        &ctx.accounts.token_program,
+        &ctx.accounts.reward_vault,
+        &ctx.accounts.voucher_token_account,
+        &ctx.accounts.mint_authority,
+        ctx.bumps.mint_authority,
+        pending,
+    )?;
+    // Only what was paid is settled; a short vault leaves the rest claimable
+    vouch.reward_debt = vouch.reward_debt.saturating_add(paid as u128);
+
+    msg!("Vouch rewards claimed: {} of {} tokens to {}", paid, pending, ctx.accounts.voucher.key());
+    Ok(())
+}
+
+/// Move `amount` from the reward vault to a voucher, signed by the mint authority PDA.
+/// Returns what was actually paid, which is less if the vault runs short.
+pub(crate) fn pay_vouch_reward<'info>(
+    token_program: &Program<'info, token::Token>,
+    reward_vault: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    mint_authority: &UncheckedAccount<'info>,
+    mint_authority_bump: u8,
+    amount: u64,
+) -> Result<u64> {
+    // Rounding can leave the vault a few units short of the books
+    let amount = amount.min(reward_vault.amount);
+    if amount == 0 {
+        return Ok(0);
+    }
+
+    let seeds: &[&[u8]] = &[b"mint_authority", &[mint_authority_bump]];
+    let signer = &[seeds];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: reward_vault.to_account_info(),
+                to: to.to_account_info(),
+                authority: mint_authority.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+    )?;
+    Ok(amount)
+}
@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use crate::state::{ReputationSnapshot, ProtocolConfig};
+use crate::errors::ReputationError;
+use crate::merkle::{snapshot_leaf, verify_proof};
+
+/// Proofs longer than this can't come from a tree the program would accept
+pub const MAX_PROOF_LEN: usize = 32;
+
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct PublishSnapshot<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = ReputationSnapshot::LEN,
+        seeds = [b"snapshot", epoch.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub snapshot: Account<'info, ReputationSnapshot>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn publish_snapshot(
+    ctx: Context<PublishSnapshot>,
+    epoch: u64,
+    merkle_root: [u8; 32],
+    agent_count: u64,
+) -> Result<()> {
+    let snapshot = &mut ctx.accounts.snapshot;
+    let clock = Clock::get()?;
+    
+    snapshot.epoch = epoch;
+    snapshot.merkle_root = merkle_root;
+    snapshot.agent_count = agent_count;
+    snapshot.published_by = ctx.accounts.authority.key();
+    snapshot.created_at = clock.unix_timestamp;
+    snapshot.bump = ctx.bumps.snapshot;
+    
+    msg!("Snapshot published: epoch {} | {} agents", epoch, agent_count);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct VerifySnapshotProof<'info> {
+    #[account(
+        seeds = [b"snapshot", snapshot.epoch.to_le_bytes().as_ref()],
+        bump = snapshot.bump,
+    )]
+    pub snapshot: Account<'info, ReputationSnapshot>,
+}
+
+pub fn verify_snapshot_proof(
+    ctx: Context<VerifySnapshotProof>,
+    agent: Pubkey,
+    score: u64,
+    proof: Vec<[u8; 32]>,
+) -> Result<()> {
+    require!(proof.len() <= MAX_PROOF_LEN, ReputationError::InvalidSnapshotProof);
+    
+    let snapshot = &ctx.accounts.snapshot;
+    let leaf = snapshot_leaf(&agent, score);
+    
+    require!(
+        verify_proof(&proof, &snapshot.merkle_root, leaf),
+        ReputationError::InvalidSnapshotProof
+    );
+    
+    msg!("Snapshot proof verified: {} had {} at epoch {}", agent, score, snapshot.epoch);
+    Ok(())
+}
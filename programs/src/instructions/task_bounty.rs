@@ -0,0 +1,688 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, CloseAccount, Mint, TokenAccount, Transfer};
+use crate::state::{
+    AgentProfile, Dispute, DisputeKind, DomainReputation, JuryConfig, PostedTask, ProtocolConfig, SkillCategory,
+    TaskRecord, TaskStatus, JURY_SEED_DELAY_SLOTS, MAX_POSTED_TASK_ID_LEN, PAUSE_GOVERNANCE, PAUSE_MINTING,
+    PAUSE_WITHDRAWALS,
+};
+use crate::errors::ReputationError;
+use super::complete_task::{credit_task_reputation, mint_task_reputation};
+use super::tier::{load_tier_config, update_tier};
+
+#[derive(Accounts)]
+#[instruction(task_id: String, bounty: u64, reputation_amount: u64, category_id: u32, deadline: i64)]
+pub struct PostTask<'info> {
+    #[account(mut)]
+    pub requester: Signer<'info>,
+
+    #[account(
+        init,
+        payer = requester,
+        space = PostedTask::LEN,
+        seeds = [b"posted_task", requester.key().as_ref(), task_id.as_bytes()],
+        bump
+    )]
+    pub task: Account<'info, PostedTask>,
+
+    /// Holds the bounty until the task is approved or refunded
+    #[account(
+        init,
+        payer = requester,
+        seeds = [b"task_escrow", task.key().as_ref()],
+        bump,
+        token::mint = reputation_mint,
+        token::authority = task,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = reputation_mint,
+        token::authority = requester,
+    )]
+    pub requester_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"category", category_id.to_le_bytes().as_ref()],
+        bump = category.bump,
+    )]
+    pub category: Account<'info, SkillCategory>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(address = config.reputation_mint)]
+    pub reputation_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, token::Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn post_task(
+    ctx: Context<PostTask>,
+    task_id: String,
+    bounty: u64,
+    reputation_amount: u64,
+    category_id: u32,
+    deadline: i64,
+) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_MINTING)?;
+    
+    let now = Clock::get()?.unix_timestamp;
+    
+    require!(task_id.len() <= MAX_POSTED_TASK_ID_LEN, ReputationError::TaskIdTooLong);
+    require!(bounty > 0, ReputationError::InvalidBounty);
+    // Requesters can't award more reputation than they put at stake
+    require!(
+        reputation_amount > 0 && reputation_amount <= bounty,
+        ReputationError::InvalidReputationAmount
+    );
+    require!(ctx.accounts.category.is_active, ReputationError::CategoryInactive);
+    require!(deadline > now, ReputationError::InvalidDeadline);
+
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.requester_token_account.to_account_info(),
+                to: ctx.accounts.escrow.to_account_info(),
+                authority: ctx.accounts.requester.to_account_info(),
+            },
+        ),
+        bounty,
+    )?;
+
+    let task = &mut ctx.accounts.task;
+    task.requester = ctx.accounts.requester.key();
+    task.agent = Pubkey::default();
+    task.task_id = task_id;
+    task.category_id = category_id;
+    task.bounty = bounty;
+    task.reputation_amount = reputation_amount;
+    task.escrow = ctx.accounts.escrow.key();
+    task.result_hash = [0; 32];
+    task.status = TaskStatus::Open;
+    task.posted_at = now;
+    task.accepted_at = 0;
+    task.submitted_at = 0;
+    task.deadline = deadline;
+    task.bump = ctx.bumps.task;
+
+    msg!("Task posted: {} | Bounty: {} | Reputation: {}", task.task_id, bounty, reputation_amount);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptTask<'info> {
+    pub agent: Signer<'info>,
+
+    #[account(
+        seeds = [b"agent", agent.key().as_ref()],
+        bump = agent_profile.bump,
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        mut,
+        seeds = [b"posted_task", task.requester.as_ref(), task.task_id.as_bytes()],
+        bump = task.bump,
+    )]
+    pub task: Account<'info, PostedTask>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+pub fn accept_task(ctx: Context<AcceptTask>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_MINTING)?;
+    
+    require!(ctx.accounts.agent_profile.is_active, ReputationError::AgentInactive);
+
+    let task = &mut ctx.accounts.task;
+    require!(task.status == TaskStatus::Open, ReputationError::InvalidTaskStatus);
+    require_keys_neq!(task.requester, ctx.accounts.agent.key(), ReputationError::Unauthorized);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now <= task.deadline, ReputationError::DeadlinePassed);
+
+    task.agent = ctx.accounts.agent.key();
+    task.status = TaskStatus::Accepted;
+    task.accepted_at = now;
+
+    msg!("Task accepted: {} by {}", task.task_id, task.agent);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SubmitTask<'info> {
+    pub agent: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"posted_task", task.requester.as_ref(), task.task_id.as_bytes()],
+        bump = task.bump,
+        has_one = agent @ ReputationError::Unauthorized,
+    )]
+    pub task: Account<'info, PostedTask>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+pub fn submit_task(ctx: Context<SubmitTask>, result_hash: [u8; 32]) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_MINTING)?;
+    
+    let task = &mut ctx.accounts.task;
+    require!(task.status == TaskStatus::Accepted, ReputationError::InvalidTaskStatus);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now <= task.deadline, ReputationError::DeadlinePassed);
+
+    task.result_hash = result_hash;
+    task.status = TaskStatus::Submitted;
+    task.submitted_at = now;
+
+    msg!("Task submitted: {}", task.task_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ApproveTask<'info> {
+    /// The requester, or anyone once a dispute was resolved for the agent
+    #[account(mut)]
+    pub settler: Signer<'info>,
+
+    /// CHECK: Receives the escrow rent; checked against the task
+    #[account(mut, address = task.requester)]
+    pub requester: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"posted_task", task.requester.as_ref(), task.task_id.as_bytes()],
+        bump = task.bump,
+    )]
+    pub task: Account<'info, PostedTask>,
+
+    /// Required when settling a disputed task
+    #[account(
+        seeds = [b"dispute", task.key().as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Option<Box<Account<'info, Dispute>>>,
+
+    #[account(
+        mut,
+        address = task.escrow,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"agent", task.agent.as_ref()],
+        bump = agent_profile.bump,
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+
+    /// CHECK: Tier config PDA, read by `load_tier_config`
+    #[account(
+        seeds = [b"tier_config"],
+        bump,
+    )]
+    pub tier_config: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = settler,
+        space = DomainReputation::LEN,
+        seeds = [b"domain", task.agent.as_ref(), task.category_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub domain_reputation: Account<'info, DomainReputation>,
+
+    #[account(
+        init,
+        payer = settler,
+        space = TaskRecord::LEN,
+        seeds = [b"posted_task_record", task.key().as_ref()],
+        bump
+    )]
+    pub task_record: Account<'info, TaskRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    /// CHECK: This is the token mint authority PDA
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = config.reputation_mint
+    )]
+    pub reputation_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        associated_token::mint = reputation_mint,
+        associated_token::authority = task.agent,
+    )]
+    pub agent_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault"],
+        bump,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, token::Token>,
+    pub system_program: Program<'info, System>,
+}
+
+pub fn approve_task(ctx: Context<ApproveTask>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_MINTING | PAUSE_WITHDRAWALS)?;
+    
+    let task = &ctx.accounts.task;
+    match task.status {
+        TaskStatus::Submitted => {
+            require_keys_eq!(ctx.accounts.settler.key(), task.requester, ReputationError::Unauthorized);
+        }
+        TaskStatus::Disputed => {
+            let dispute = ctx.accounts.dispute.as_ref().ok_or(ReputationError::DisputeNotResolved)?;
+            require!(dispute.resolved, ReputationError::DisputeNotResolved);
+            require!(dispute.is_final(Clock::get()?.unix_timestamp), ReputationError::AppealWindowOpen);
+            require!(dispute.agent_won, ReputationError::InvalidTaskStatus);
+        }
+        _ => return err!(ReputationError::InvalidTaskStatus),
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let reputation_amount = task.reputation_amount;
+    let category_id = task.category_id;
+
+    release_escrow(
+        &ctx.accounts.task,
+        &ctx.accounts.escrow,
+        &ctx.accounts.agent_token_account,
+        &ctx.accounts.requester,
+        &ctx.accounts.token_program,
+    )?;
+
+    credit_task_reputation(
+        &mut ctx.accounts.agent_profile,
+        &mut ctx.accounts.domain_reputation,
+        ctx.bumps.domain_reputation,
+        category_id,
+        reputation_amount,
+        now,
+    );
+
+    let record = &mut ctx.accounts.task_record;
+    record.agent = ctx.accounts.task.agent;
+    record.task_id = ctx.accounts.task.task_id.clone();
+    record.reputation_earned = reputation_amount;
+    record.completed_at = now;
+    record.category_id = category_id;
+    record.requester = ctx.accounts.task.requester;
+    record.rating = 0;
+    record.review_hash = [0; 32];
+    record.missed_deadline = false;
+    record.reputation_penalty = 0;
+    record.posted_task = ctx.accounts.task.key();
+    record.bump = ctx.bumps.task_record;
+
+    mint_task_reputation(
+        &mut ctx.accounts.agent_profile,
+        &mut ctx.accounts.config,
+        &ctx.accounts.token_program,
+        &ctx.accounts.reputation_mint,
+        &ctx.accounts.agent_token_account,
+        &ctx.accounts.reward_vault,
+        &ctx.accounts.mint_authority,
+        ctx.bumps.mint_authority,
+        reputation_amount,
+        now,
+    )?;
+    update_tier(&mut ctx.accounts.agent_profile, load_tier_config(&ctx.accounts.tier_config)?.as_ref(), now);
+
+    let task = &mut ctx.accounts.task;
+    task.status = TaskStatus::Completed;
+
+    msg!("Task approved: {} | Bounty {} released to {}", task.task_id, task.bounty, task.agent);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RejectTask<'info> {
+    #[account(mut)]
+    pub requester: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"posted_task", requester.key().as_ref(), task.task_id.as_bytes()],
+        bump = task.bump,
+        has_one = requester @ ReputationError::Unauthorized,
+    )]
+    pub task: Account<'info, PostedTask>,
+
+    #[account(
+        init,
+        payer = requester,
+        space = Dispute::LEN,
+        seeds = [b"dispute", task.key().as_ref()],
+        bump
+    )]
+    pub dispute: Box<Account<'info, Dispute>>,
+
+    #[account(
+        mut,
+        seeds = [b"agent", task.agent.as_ref()],
+        bump = agent_profile.bump,
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        seeds = [b"jury_config"],
+        bump = jury_config.bump,
+    )]
+    pub jury_config: Account<'info, JuryConfig>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+pub fn reject_task(ctx: Context<RejectTask>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_MINTING | PAUSE_GOVERNANCE)?;
+    
+    let jury_config = &ctx.accounts.jury_config;
+    let task = &mut ctx.accounts.task;
+    require!(task.status == TaskStatus::Submitted, ReputationError::InvalidTaskStatus);
+    let now = Clock::get()?.unix_timestamp;
+
+    task.status = TaskStatus::Disputed;
+
+    let dispute = &mut ctx.accounts.dispute;
+    dispute.subject = task.key();
+    dispute.kind = DisputeKind::Task;
+    dispute.claimant = task.requester;
+    dispute.agent = task.agent;
+    dispute.opened_at = now;
+    dispute.round = 0;
+    dispute.resolved = false;
+    dispute.agent_won = false;
+    dispute.appealable_until = 0;
+    dispute.appeal_count = 0;
+    dispute.appellant = Pubkey::default();
+    dispute.appeal_bond = 0;
+    dispute.appealed_verdict = false;
+    dispute.closed = false;
+    // The bounty already stands in for the requester's bond
+    dispute.claimant_bond = 0;
+    dispute.summon_deadline = now.saturating_add(jury_config.summon_period);
+    dispute.seed_slot = Clock::get()?.slot.saturating_add(JURY_SEED_DELAY_SLOTS);
+    dispute.snapshot_pool(jury_config);
+    require!(
+        dispute.pool().len() >= jury_config.jury_size_for_round(1),
+        ReputationError::NotEnoughJurors
+    );
+    dispute.bump = ctx.bumps.dispute;
+
+    let profile = &mut ctx.accounts.agent_profile;
+    profile.open_disputes = profile.open_disputes.saturating_add(1);
+
+    msg!("Task rejected: {} | Dispute opened for a jury", task.task_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefundTask<'info> {
+    pub caller: Signer<'info>,
+
+    /// CHECK: Receives the bounty's rent; checked against the task
+    #[account(mut, address = task.requester)]
+    pub requester: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"posted_task", task.requester.as_ref(), task.task_id.as_bytes()],
+        bump = task.bump,
+    )]
+    pub task: Account<'info, PostedTask>,
+
+    /// Required when refunding a disputed task
+    #[account(
+        seeds = [b"dispute", task.key().as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Option<Box<Account<'info, Dispute>>>,
+
+    #[account(
+        mut,
+        address = task.escrow,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = escrow.mint,
+        token::authority = task.requester,
+    )]
+    pub requester_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub token_program: Program<'info, token::Token>,
+}
+
+/// Return the bounty to the requester: cancels an open task, or settles a
+/// dispute the requester won. Overdue tasks go through `expire_task`.
+pub fn refund_task(ctx: Context<RefundTask>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_WITHDRAWALS)?;
+    
+    let task = &ctx.accounts.task;
+    match task.status {
+        TaskStatus::Open => {
+            require_keys_eq!(ctx.accounts.caller.key(), task.requester, ReputationError::Unauthorized);
+        }
+        TaskStatus::Disputed => {
+            let dispute = ctx.accounts.dispute.as_ref().ok_or(ReputationError::DisputeNotResolved)?;
+            // A dispute voided before any jury sat leaves nobody to rule for the agent
+            if !dispute.is_voided() {
+                require!(dispute.resolved, ReputationError::DisputeNotResolved);
+                require!(dispute.is_final(Clock::get()?.unix_timestamp), ReputationError::AppealWindowOpen);
+                require!(!dispute.agent_won, ReputationError::InvalidTaskStatus);
+            }
+        }
+        _ => return err!(ReputationError::InvalidTaskStatus),
+    }
+
+    release_escrow(
+        &ctx.accounts.task,
+        &ctx.accounts.escrow,
+        &ctx.accounts.requester_token_account,
+        &ctx.accounts.requester,
+        &ctx.accounts.token_program,
+    )?;
+
+    let task = &mut ctx.accounts.task;
+    task.status = TaskStatus::Refunded;
+
+    msg!("Task refunded: {} | Bounty {} returned to {}", task.task_id, task.bounty, task.requester);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ExpireTask<'info> {
+    /// Anyone can expire an overdue task; pays for the agent's task record
+    #[account(mut)]
+    pub caller: Signer<'info>,
+
+    /// CHECK: Receives the escrow rent; checked against the task
+    #[account(mut, address = task.requester)]
+    pub requester: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"posted_task", task.requester.as_ref(), task.task_id.as_bytes()],
+        bump = task.bump,
+    )]
+    pub task: Account<'info, PostedTask>,
+
+    #[account(
+        mut,
+        address = task.escrow,
+    )]
+    pub escrow: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = escrow.mint,
+        token::authority = task.requester,
+    )]
+    pub requester_token_account: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"agent", task.agent.as_ref()],
+        bump = agent_profile.bump,
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+
+    /// CHECK: Tier config PDA, read by `load_tier_config`
+    #[account(
+        seeds = [b"tier_config"],
+        bump,
+    )]
+    pub tier_config: UncheckedAccount<'info>,
+
+    #[account(
+        init,
+        payer = caller,
+        space = TaskRecord::LEN,
+        seeds = [b"posted_task_record", task.key().as_ref()],
+        bump
+    )]
+    pub task_record: Account<'info, TaskRecord>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    pub token_program: Program<'info, token::Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Expire an accepted task that missed its deadline: refund the requester
+/// and penalise the agent
+pub fn expire_task(ctx: Context<ExpireTask>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_WITHDRAWALS)?;
+    
+    let task = &ctx.accounts.task;
+    require!(task.status == TaskStatus::Accepted, ReputationError::InvalidTaskStatus);
+
+    let now = Clock::get()?.unix_timestamp;
+    require!(now > task.deadline, ReputationError::DeadlineNotPassed);
+
+    release_escrow(
+        &ctx.accounts.task,
+        &ctx.accounts.escrow,
+        &ctx.accounts.requester_token_account,
+        &ctx.accounts.requester,
+        &ctx.accounts.token_program,
+    )?;
+
+    let profile = &mut ctx.accounts.agent_profile;
+    let penalty = ctx.accounts.config.missed_deadline_penalty.min(profile.reputation_score);
+    let score = profile.reputation_score - penalty;
+    profile.set_reputation_score(score, now);
+    update_tier(profile, load_tier_config(&ctx.accounts.tier_config)?.as_ref(), now);
+
+    let task = &mut ctx.accounts.task;
+    task.status = TaskStatus::Expired;
+
+    let record = &mut ctx.accounts.task_record;
+    record.agent = task.agent;
+    record.task_id = task.task_id.clone();
+    record.reputation_earned = 0;
+    record.completed_at = now;
+    record.category_id = task.category_id;
+    record.requester = task.requester;
+    record.rating = 0;
+    record.review_hash = [0; 32];
+    record.missed_deadline = true;
+    record.reputation_penalty = penalty;
+    record.posted_task = task.key();
+    record.bump = ctx.bumps.task_record;
+
+    msg!("Task expired: {} | Agent {} penalised {} | Bounty {} refunded", 
+        task.task_id, task.agent, penalty, task.bounty);
+    Ok(())
+}
+
+/// Pay out the whole escrow and close it, returning its rent to the requester
+pub(crate) fn release_escrow<'info>(
+    task: &Account<'info, PostedTask>,
+    escrow: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    requester: &UncheckedAccount<'info>,
+    token_program: &Program<'info, token::Token>,
+) -> Result<()> {
+    let seeds: &[&[u8]] = &[
+        b"posted_task",
+        task.requester.as_ref(),
+        task.task_id.as_bytes(),
+        &[task.bump],
+    ];
+    let signer = &[seeds];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            Transfer {
+                from: escrow.to_account_info(),
+                to: to.to_account_info(),
+                authority: task.to_account_info(),
+            },
+            signer,
+        ),
+        escrow.amount,
+    )?;
+
+    token::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: escrow.to_account_info(),
+            destination: requester.to_account_info(),
+            authority: task.to_account_info(),
+        },
+        signer,
+    ))
+}
@@ -0,0 +1,115 @@
+use anchor_lang::prelude::*;
+use crate::state::{AgentProfile, ProtocolConfig, TierConfig, TierThreshold, MAX_TIERS, PAUSE_MINTING};
+use crate::events::TierChanged;
+use crate::errors::ReputationError;
+
+#[derive(Accounts)]
+pub struct SetTiers<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = TierConfig::LEN,
+        seeds = [b"tier_config"],
+        bump
+    )]
+    pub tier_config: Account<'info, TierConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/// Replace the tier thresholds. Cached tiers catch up as profiles are
+/// updated or refreshed.
+pub fn set_tiers(ctx: Context<SetTiers>, thresholds: Vec<TierThreshold>) -> Result<()> {
+    require!(!thresholds.is_empty() && thresholds.len() <= MAX_TIERS, ReputationError::InvalidTierThresholds);
+    // Each tier must be strictly harder to reach than the one below it
+    require!(
+        thresholds.windows(2).all(|w| {
+            w[1].min_score >= w[0].min_score
+                && w[1].min_positive_vouches >= w[0].min_positive_vouches
+                && (w[1].min_score > w[0].min_score || w[1].min_positive_vouches > w[0].min_positive_vouches)
+        }),
+        ReputationError::InvalidTierThresholds
+    );
+
+    let tier_config = &mut ctx.accounts.tier_config;
+    tier_config.thresholds = [TierThreshold::default(); MAX_TIERS];
+    tier_config.thresholds[..thresholds.len()].copy_from_slice(&thresholds);
+    tier_config.tier_count = thresholds.len() as u8;
+    tier_config.bump = ctx.bumps.tier_config;
+
+    msg!("Tier thresholds set: {} tiers", thresholds.len());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RefreshTier<'info> {
+    #[account(
+        mut,
+        seeds = [b"agent", agent_profile.owner.as_ref()],
+        bump = agent_profile.bump,
+    )]
+    pub agent_profile: Account<'info, AgentProfile>,
+
+    #[account(
+        seeds = [b"tier_config"],
+        bump = tier_config.bump,
+    )]
+    pub tier_config: Account<'info, TierConfig>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+}
+
+/// Recompute an agent's cached tier. Anyone can call this, e.g. after vouch
+/// counts or the thresholds changed.
+pub fn refresh_tier(ctx: Context<RefreshTier>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_MINTING)?;
+
+    let tier_config: &TierConfig = &ctx.accounts.tier_config;
+    update_tier(&mut ctx.accounts.agent_profile, Some(tier_config), Clock::get()?.unix_timestamp);
+    Ok(())
+}
+
+/// Read the tier config PDA. Instructions that move a score or a vouch count
+/// always take the account, so a caller can't leave it out to dodge a tier
+/// change; it is `None` only until `set_tiers` has created it.
+pub(crate) fn load_tier_config(info: &AccountInfo) -> Result<Option<TierConfig>> {
+    if info.data_is_empty() {
+        return Ok(None);
+    }
+    require_keys_eq!(*info.owner, crate::ID, ErrorCode::AccountOwnedByWrongProgram);
+    let data = info.try_borrow_data()?;
+    Ok(Some(TierConfig::try_deserialize(&mut &data[..])?))
+}
+
+/// Recompute the cached tier and emit `TierChanged` if it moved. A no-op
+/// until tiers are configured.
+pub(crate) fn update_tier(profile: &mut AgentProfile, tier_config: Option<&TierConfig>, now: i64) {
+    if let Some(tier_config) = tier_config {
+        let new_tier = tier_config.tier_for(profile);
+        if new_tier != profile.tier {
+            emit!(TierChanged {
+                agent: profile.owner,
+                old_tier: profile.tier,
+                new_tier,
+                reputation_score: profile.reputation_score,
+                timestamp: now,
+            });
+            msg!("Tier changed: {} {} -> {}", profile.owner, profile.tier, new_tier);
+            profile.tier = new_tier;
+        }
+    }
+}
@@ -0,0 +1,157 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Mint, TokenAccount, Transfer};
+use crate::state::{ProtocolConfig, Treasury, PAUSE_WITHDRAWALS};
+use crate::errors::ReputationError;
+
+#[derive(Accounts)]
+pub struct InitializeTreasury<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = Treasury::LEN,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"treasury_vault"],
+        bump,
+        token::mint = reputation_mint,
+        token::authority = treasury,
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(address = config.reputation_mint)]
+    pub reputation_mint: Account<'info, Mint>,
+
+    pub token_program: Program<'info, token::Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+    let treasury = &mut ctx.accounts.treasury;
+
+    treasury.vault = ctx.accounts.treasury_vault.key();
+    treasury.total_token_fees = 0;
+    treasury.total_sol_fees = 0;
+    treasury.total_slashed = 0;
+    treasury.total_sol_slashed = 0;
+    treasury.bump = ctx.bumps.treasury;
+
+    msg!("Treasury initialized with vault {}", treasury.vault);
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasury<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    #[account(
+        mut,
+        address = treasury.vault,
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = config.reputation_mint,
+    )]
+    pub destination: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, token::Token>,
+}
+
+pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_WITHDRAWALS)?;
+
+    require!(
+        ctx.accounts.treasury_vault.amount >= amount,
+        ReputationError::InsufficientTreasuryBalance
+    );
+
+    let seeds: &[&[u8]] = &[b"treasury", &[ctx.accounts.treasury.bump]];
+    let signer = &[seeds];
+
+    token::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.treasury_vault.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+                authority: ctx.accounts.treasury.to_account_info(),
+            },
+            signer,
+        ),
+        amount,
+    )?;
+
+    msg!("Treasury withdrawal: {} tokens to {}", amount, ctx.accounts.destination.key());
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct WithdrawTreasurySol<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+        has_one = authority @ ReputationError::Unauthorized,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+
+    /// CHECK: Any system account can receive lamports
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+}
+
+pub fn withdraw_treasury_sol(ctx: Context<WithdrawTreasurySol>, lamports: u64) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_WITHDRAWALS)?;
+
+    let treasury_info = ctx.accounts.treasury.to_account_info();
+    let rent_floor = Rent::get()?.minimum_balance(treasury_info.data_len());
+    let available = treasury_info.lamports().saturating_sub(rent_floor);
+
+    require!(available >= lamports, ReputationError::InsufficientTreasuryBalance);
+
+    // The treasury is program-owned, so lamports can be moved directly
+    **treasury_info.try_borrow_mut_lamports()? -= lamports;
+    **ctx.accounts.destination.to_account_info().try_borrow_mut_lamports()? += lamports;
+
+    msg!("Treasury withdrawal: {} lamports to {}", lamports, ctx.accounts.destination.key());
+    Ok(())
+}
@@ -1,7 +1,9 @@
 use anchor_lang::prelude::*;
 use anchor_spl::token::{self, TokenAccount, Transfer};
-use crate::state::{AgentProfile, VouchRecord, ProtocolConfig};
+use crate::state::{AgentProfile, Dispute, DisputeKind, VouchRecord, ProtocolConfig, Treasury, calculate_fee, calculate_accrued_reward, calculate_pending_reward, PAUSE_VOUCHING, PAUSE_WITHDRAWALS};
+use crate::instructions::rewards::pay_vouch_reward;
 use crate::errors::ReputationError;
+use super::tier::{load_tier_config, update_tier};
 
 #[derive(Accounts)]
 #[instruction(amount: u64)]
@@ -23,6 +25,13 @@ pub struct VouchFor<'info> {
     )]
     pub vouched_for_profile: Account<'info, AgentProfile>,
     
+    /// CHECK: Tier config PDA, read by `load_tier_config`
+    #[account(
+        seeds = [b"tier_config"],
+        bump,
+    )]
+    pub tier_config: UncheckedAccount<'info>,
+    
     /// CHECK: Just the pubkey of the agent being vouched for
     #[account(mut)]
     pub vouched_for: UncheckedAccount<'info>,
@@ -63,37 +72,67 @@ pub struct VouchFor<'info> {
     )]
     pub vouch_escrow: UncheckedAccount<'info>,
     
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    
+    #[account(
+        mut,
+        address = treasury.vault,
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    
     pub token_program: Program<'info, token::Token>,
     pub system_program: Program<'info, System>,
     pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn vouch_for(ctx: Context<VouchFor>, amount: u64) -> Result<()> {
+pub fn vouch_for(ctx: Context<VouchFor>, amount: u64, expires_at: Option<i64>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_VOUCHING)?;
+    
     let voucher_key = ctx.accounts.voucher.key();
     let vouched_for_key = ctx.accounts.vouched_for.key();
     
     require!(voucher_key != vouched_for_key, ReputationError::SelfVouchNotAllowed);
+    require!(ctx.accounts.voucher_profile.is_active, ReputationError::AgentInactive);
     require!(
         ctx.accounts.voucher_profile.reputation_score >= ctx.accounts.config.min_reputation_for_vouching,
         ReputationError::InsufficientReputation
     );
     
     let clock = Clock::get()?;
+    let expires_at = ctx.accounts.config.validate_vouch_expiry(expires_at, clock.unix_timestamp)?;
+    
+    // Protocol fee comes out of the stake; only the remainder is escrowed
+    let fee = calculate_fee(amount, ctx.accounts.config.vouch_fee_bps);
+    let stake = amount.checked_sub(fee).ok_or(ReputationError::MathOverflow)?;
     
     // Update vouch record
     let vouch = &mut ctx.accounts.vouch_record;
     vouch.voucher = voucher_key;
     vouch.vouched_for = vouched_for_key;
-    vouch.amount = amount;
+    vouch.amount = stake;
     vouch.is_positive = true;
+    vouch.is_escrowed = true;
     vouch.created_at = clock.unix_timestamp;
+    vouch.reward_debt = calculate_accrued_reward(stake, ctx.accounts.vouched_for_profile.reward_per_share);
+    vouch.init_tranches(stake, clock.unix_timestamp);
+    vouch.expires_at = expires_at;
     vouch.bump = ctx.bumps.vouch_record;
     
     // Update profiles
     ctx.accounts.vouched_for_profile.positive_vouches = 
         ctx.accounts.vouched_for_profile.positive_vouches.saturating_add(1);
+    ctx.accounts.vouched_for_profile.total_vouch_stake = 
+        ctx.accounts.vouched_for_profile.total_vouch_stake.saturating_add(stake);
+    ctx.accounts.vouched_for_profile.push_vouch_ramp(
+        true, clock.unix_timestamp, ctx.accounts.config.vouch_lockup_period);
+    update_tier(&mut ctx.accounts.vouched_for_profile, load_tier_config(&ctx.accounts.tier_config)?.as_ref(), clock.unix_timestamp);
     ctx.accounts.voucher_profile.staked_amount = 
-        ctx.accounts.voucher_profile.staked_amount.saturating_add(amount);
+        ctx.accounts.voucher_profile.staked_amount.saturating_add(stake);
     
     // Transfer tokens to escrow
     token::transfer(
@@ -105,11 +144,27 @@ pub fn vouch_for(ctx: Context<VouchFor>, amount: u64) -> Result<()> {
                 authority: ctx.accounts.voucher.to_account_info(),
             },
         ),
-        amount,
+        stake,
     )?;
     
-    msg!("Positive vouch: {} staked {} reputation for {}", 
-        voucher_key, amount, vouched_for_key);
+    if fee > 0 {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.voucher_token_account.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: ctx.accounts.voucher.to_account_info(),
+                },
+            ),
+            fee,
+        )?;
+        ctx.accounts.treasury.total_token_fees = 
+            ctx.accounts.treasury.total_token_fees.saturating_add(fee);
+    }
+    
+    msg!("Positive vouch: {} staked {} reputation for {} (fee {})", 
+        voucher_key, stake, vouched_for_key, fee);
     Ok(())
 }
 
@@ -152,36 +207,117 @@ pub struct VouchAgainst<'info> {
     )]
     pub config: Account<'info, ProtocolConfig>,
     
+    #[account(
+        mut,
+        associated_token::mint = config.reputation_mint,
+        associated_token::authority = voucher,
+    )]
+    pub voucher_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = config.reputation_mint,
+        associated_token::authority = vouch_escrow,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the escrow account
+    #[account(
+        seeds = [b"escrow", vouch_record.key().as_ref()],
+        bump,
+    )]
+    pub vouch_escrow: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    
+    #[account(
+        mut,
+        address = treasury.vault,
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, token::Token>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn vouch_against(ctx: Context<VouchAgainst>, amount: u64) -> Result<()> {
+pub fn vouch_against(ctx: Context<VouchAgainst>, amount: u64, expires_at: Option<i64>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_VOUCHING)?;
+    
     let voucher_key = ctx.accounts.voucher.key();
     let vouched_against_key = ctx.accounts.vouched_against.key();
     
     require!(voucher_key != vouched_against_key, ReputationError::SelfVouchNotAllowed);
+    require!(ctx.accounts.voucher_profile.is_active, ReputationError::AgentInactive);
     require!(
         ctx.accounts.voucher_profile.reputation_score >= ctx.accounts.config.min_reputation_for_vouching,
         ReputationError::InsufficientReputation
     );
     
     let clock = Clock::get()?;
+    let expires_at = ctx.accounts.config.validate_vouch_expiry(expires_at, clock.unix_timestamp)?;
+    
+    // Dispute filing fee comes out of the stake; the remainder is escrowed so
+    // a challenge the agent beats can be slashed
+    let fee = calculate_fee(amount, ctx.accounts.config.dispute_fee_bps);
+    let stake = amount.checked_sub(fee).ok_or(ReputationError::MathOverflow)?;
     
     // Update vouch record
     let vouch = &mut ctx.accounts.vouch_record;
     vouch.voucher = voucher_key;
     vouch.vouched_for = vouched_against_key;
-    vouch.amount = amount;
+    vouch.amount = stake;
     vouch.is_positive = false;
+    vouch.is_escrowed = true;
     vouch.created_at = clock.unix_timestamp;
+    vouch.reward_debt = 0;
+    vouch.init_tranches(stake, clock.unix_timestamp);
+    vouch.expires_at = expires_at;
     vouch.bump = ctx.bumps.vouch_record;
     
-    // Update profile
+    // Update profiles
     ctx.accounts.vouched_against_profile.negative_vouches = 
         ctx.accounts.vouched_against_profile.negative_vouches.saturating_add(1);
+    ctx.accounts.vouched_against_profile.push_vouch_ramp(
+        false, clock.unix_timestamp, ctx.accounts.config.vouch_lockup_period);
+    ctx.accounts.voucher_profile.staked_amount = 
+        ctx.accounts.voucher_profile.staked_amount.saturating_add(stake);
     
-    msg!("Negative vouch: {} challenged {} with {} stake", 
-        voucher_key, vouched_against_key, amount);
+    // Transfer tokens to escrow
+    token::transfer(
+        CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.voucher_token_account.to_account_info(),
+                to: ctx.accounts.escrow_token_account.to_account_info(),
+                authority: ctx.accounts.voucher.to_account_info(),
+            },
+        ),
+        stake,
+    )?;
+    
+    if fee > 0 {
+        token::transfer(
+            CpiContext::new(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.voucher_token_account.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: ctx.accounts.voucher.to_account_info(),
+                },
+            ),
+            fee,
+        )?;
+        ctx.accounts.treasury.total_token_fees = 
+            ctx.accounts.treasury.total_token_fees.saturating_add(fee);
+    }
+    
+    msg!("Negative vouch: {} challenged {} with {} stake (fee {})", 
+        voucher_key, vouched_against_key, stake, fee);
     Ok(())
 }
 
@@ -209,6 +345,20 @@ pub struct WithdrawVouch<'info> {
     #[account(mut)]
     pub vouched_for: UncheckedAccount<'info>,
     
+    #[account(
+        mut,
+        seeds = [b"agent", vouched_for.key().as_ref()],
+        bump = vouched_for_profile.bump,
+    )]
+    pub vouched_for_profile: Account<'info, AgentProfile>,
+    
+    /// CHECK: Tier config PDA, read by `load_tier_config`
+    #[account(
+        seeds = [b"tier_config"],
+        bump,
+    )]
+    pub tier_config: UncheckedAccount<'info>,
+    
     #[account(
         seeds = [b"config"],
         bump = config.bump,
@@ -222,12 +372,13 @@ pub struct WithdrawVouch<'info> {
     )]
     pub voucher_token_account: Account<'info, TokenAccount>,
     
+    /// Only needed when the vouch has escrowed stake
     #[account(
         mut,
         associated_token::mint = config.reputation_mint,
         associated_token::authority = vouch_escrow,
     )]
-    pub escrow_token_account: Account<'info, TokenAccount>,
+    pub escrow_token_account: Option<Account<'info, TokenAccount>>,
     
     /// CHECK: PDA that owns the escrow account
     #[account(
@@ -236,31 +387,54 @@ pub struct WithdrawVouch<'info> {
     )]
     pub vouch_escrow: UncheckedAccount<'info>,
     
+    #[account(
+        mut,
+        seeds = [b"reward_vault"],
+        bump,
+    )]
+    pub reward_vault: Account<'info, TokenAccount>,
+    
+    /// CHECK: This is the token mint authority PDA
+    #[account(
+        seeds = [b"mint_authority"],
+        bump,
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
     pub token_program: Program<'info, token::Token>,
     pub system_program: Program<'info, System>,
 }
 
 pub fn withdraw_vouch(ctx: Context<WithdrawVouch>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_WITHDRAWALS)?;
+    require!(
+        ctx.accounts.vouched_for_profile.open_disputes == 0,
+        ReputationError::AgentUnderDispute
+    );
+    
     let vouch = &ctx.accounts.vouch_record;
     let clock = Clock::get()?;
     
-    // Check lockup period
-    let elapsed = clock.unix_timestamp.saturating_sub(vouch.created_at);
+    // Check lockup period (every tranche, including top-ups)
     require!(
-        elapsed >= ctx.accounts.config.vouch_lockup_period,
+        vouch.is_fully_unlocked(clock.unix_timestamp, ctx.accounts.config.vouch_lockup_period),
         ReputationError::LockupNotExpired
     );
     
     // Return staked tokens
-    if vouch.amount > 0 && vouch.is_positive {
-        let seeds = &[b"escrow", vouch.key().as_ref(), &[ctx.bumps.vouch_escrow]];
+    if vouch.amount > 0 && vouch.is_escrowed {
+        let escrow_token_account = ctx.accounts.escrow_token_account
+            .as_ref()
+            .ok_or(ReputationError::NoEscrowedStake)?;
+        let vouch_key = vouch.key();
+        let seeds = &[b"escrow", vouch_key.as_ref(), &[ctx.bumps.vouch_escrow]];
         let signer = &[&seeds[..]];
         
         token::transfer(
             CpiContext::new_with_signer(
                 ctx.accounts.token_program.to_account_info(),
                 Transfer {
-                    from: ctx.accounts.escrow_token_account.to_account_info(),
+                    from: escrow_token_account.to_account_info(),
                     to: ctx.accounts.voucher_token_account.to_account_info(),
                     authority: ctx.accounts.vouch_escrow.to_account_info(),
                 },
@@ -270,10 +444,176 @@ pub fn withdraw_vouch(ctx: Context<WithdrawVouch>) -> Result<()> {
         )?;
     }
     
-    // Update profile
+    // Pay out anything accrued so it isn't lost when the record closes
+    if vouch.is_positive {
+        let pending = calculate_pending_reward(
+            vouch.amount,
+            ctx.accounts.vouched_for_profile.reward_per_share,
+            vouch.reward_debt,
+        );
+        pay_vouch_reward(
+            &ctx.accounts.token_program,
+            &ctx.accounts.reward_vault,
+            &ctx.accounts.voucher_token_account,
+            &ctx.accounts.mint_authority,
+            ctx.bumps.mint_authority,
+            pending,
+        )?;
+    }
+    
+    // Update profiles
+    ctx.accounts.vouched_for_profile.remove_vouch(vouch, clock.unix_timestamp, ctx.accounts.config.vouch_lockup_period);
+    update_tier(&mut ctx.accounts.vouched_for_profile, load_tier_config(&ctx.accounts.tier_config)?.as_ref(), clock.unix_timestamp);
+    if vouch.is_escrowed {
+        ctx.accounts.voucher_profile.staked_amount = 
+            ctx.accounts.voucher_profile.staked_amount.saturating_sub(vouch.amount);
+    }
+    
+    msg!("Vouch withdrawn. Returned {} tokens", if vouch.is_escrowed { vouch.amount } else { 0 });
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct SlashVouch<'info> {
+    /// The config authority, or anyone once a jury has ruled against the vouch
+    pub slasher: Signer<'info>,
+    
+    #[account(
+        seeds = [b"config"],
+        bump = config.bump,
+    )]
+    pub config: Account<'info, ProtocolConfig>,
+    
+    /// Resolved vouch dispute about the vouched-for agent
+    #[account(
+        seeds = [b"dispute", dispute.subject.as_ref()],
+        bump = dispute.bump,
+    )]
+    pub dispute: Option<Box<Account<'info, Dispute>>>,
+    
+    #[account(
+        mut,
+        close = voucher,
+        seeds = [b"vouch", voucher.key().as_ref(), vouch_record.vouched_for.as_ref()],
+        bump = vouch_record.bump,
+        constraint = vouch_record.is_escrowed @ ReputationError::NoEscrowedStake,
+    )]
+    pub vouch_record: Account<'info, VouchRecord>,
+    
+    /// CHECK: Receives the vouch record's rent; verified by the record's seeds
+    #[account(mut)]
+    pub voucher: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"agent", voucher.key().as_ref()],
+        bump = voucher_profile.bump,
+    )]
+    pub voucher_profile: Account<'info, AgentProfile>,
+    
+    #[account(
+        mut,
+        seeds = [b"agent", vouch_record.vouched_for.as_ref()],
+        bump = vouched_for_profile.bump,
+    )]
+    pub vouched_for_profile: Account<'info, AgentProfile>,
+    
+    /// CHECK: Tier config PDA, read by `load_tier_config`
+    #[account(
+        seeds = [b"tier_config"],
+        bump,
+    )]
+    pub tier_config: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        associated_token::mint = config.reputation_mint,
+        associated_token::authority = vouch_escrow,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    /// CHECK: PDA that owns the escrow account
+    #[account(
+        seeds = [b"escrow", vouch_record.key().as_ref()],
+        bump,
+    )]
+    pub vouch_escrow: UncheckedAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump = treasury.bump,
+    )]
+    pub treasury: Account<'info, Treasury>,
+    
+    #[account(
+        mut,
+        address = treasury.vault,
+    )]
+    pub treasury_vault: Account<'info, TokenAccount>,
+    
+    pub token_program: Program<'info, token::Token>,
+}
+
+pub fn slash_vouch(ctx: Context<SlashVouch>) -> Result<()> {
+    ctx.accounts.config.require_not_paused(PAUSE_WITHDRAWALS)?;
+    
+    let vouch = &ctx.accounts.vouch_record;
+    if ctx.accounts.slasher.key() != ctx.accounts.config.authority {
+        let dispute = ctx.accounts.dispute.as_ref().ok_or(ReputationError::Unauthorized)?;
+        require!(dispute.is_final(Clock::get()?.unix_timestamp), ReputationError::AppealWindowOpen);
+        require!(is_slashable_by(vouch, dispute), ReputationError::Unauthorized);
+    } else {
+        // A pending jury decides these stakes, not the authority
+        require!(
+            ctx.accounts.vouched_for_profile.open_disputes == 0,
+            ReputationError::AgentUnderDispute
+        );
+    }
+    let slashed = ctx.accounts.escrow_token_account.amount.min(vouch.amount);
+    
+    // Escrowed stake moves to the treasury instead of back to the voucher
+    if slashed > 0 {
+        let vouch_key = vouch.key();
+        let seeds = &[b"escrow", vouch_key.as_ref(), &[ctx.bumps.vouch_escrow]];
+        let signer = &[&seeds[..]];
+        
+        token::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.escrow_token_account.to_account_info(),
+                    to: ctx.accounts.treasury_vault.to_account_info(),
+                    authority: ctx.accounts.vouch_escrow.to_account_info(),
+                },
+                signer,
+            ),
+            slashed,
+        )?;
+    }
+    
     ctx.accounts.voucher_profile.staked_amount = 
         ctx.accounts.voucher_profile.staked_amount.saturating_sub(vouch.amount);
-    
-    msg!("Vouch withdrawn. Returned {} tokens", vouch.amount);
+    let lockup = ctx.accounts.config.vouch_lockup_period;
+    let now = Clock::get()?.unix_timestamp;
+    ctx.accounts.vouched_for_profile.remove_vouch(vouch, now, lockup);
+    update_tier(&mut ctx.accounts.vouched_for_profile, load_tier_config(&ctx.accounts.tier_config)?.as_ref(), now);
+    ctx.accounts.treasury.total_slashed = 
+        ctx.accounts.treasury.total_slashed.saturating_add(slashed);
+    
+    msg!("Vouch slashed: {} tokens from {} moved to treasury", slashed, vouch.voucher);
     Ok(())
 }
+
+/// A jury verdict slashes the losing side: the challenger's negative vouch if
+/// the agent won, otherwise the positive vouches that predate the dispute
+fn is_slashable_by(vouch: &Account<VouchRecord>, dispute: &Dispute) -> bool {
+    if dispute.kind != DisputeKind::Vouch || !dispute.resolved || vouch.vouched_for != dispute.agent {
+        return false;
+    }
+    if dispute.agent_won {
+        vouch.key() == dispute.subject
+    } else {
+        vouch.is_positive && vouch.created_at <= dispute.opened_at
+    }
+}
@@ -1,13 +1,14 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Token, TokenAccount, Mint, Transfer};
 
-mod state;
+pub mod state;
+pub mod merkle;
+pub mod receipt;
+pub mod events;
 mod instructions;
 mod errors;
 
 use state::*;
 use instructions::*;
-use errors::*;
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
@@ -20,28 +21,216 @@ pub mod agentreputation_dao {
         instructions::initialize(ctx, config)
     }
 
+    /// Pause instruction groups (guardian or authority)
+    pub fn pause(ctx: Context<Pause>, groups: u8) -> Result<()> {
+        instructions::pause(ctx, groups)
+    }
+
+    /// Unpause instruction groups (authority only)
+    pub fn unpause(ctx: Context<UpdateConfig>, groups: u8) -> Result<()> {
+        instructions::unpause(ctx, groups)
+    }
+
     /// Register a new agent in the reputation system
     pub fn register_agent(ctx: Context<RegisterAgent>, agent_name: String) -> Result<()> {
         instructions::register_agent(ctx, agent_name)
     }
 
+    /// Deregister an agent and reclaim its registration bond
+    pub fn deregister_agent(ctx: Context<DeregisterAgent>) -> Result<()> {
+        instructions::deregister_agent(ctx)
+    }
+
+    /// Slash a sybil agent's registration bond into the treasury
+    pub fn slash_bond(ctx: Context<SlashBond>) -> Result<()> {
+        instructions::slash_bond(ctx)
+    }
+
+    /// Close the profile of an agent whose bond was slashed
+    pub fn close_slashed_agent(ctx: Context<CloseSlashedAgent>) -> Result<()> {
+        instructions::close_slashed_agent(ctx)
+    }
+
     /// Complete a task and earn reputation
     pub fn complete_task(
         ctx: Context<CompleteTask>,
         task_id: String,
         reputation_amount: u64,
+        category_id: u32,
+        receipt: Option<receipt::TaskReceipt>,
+    ) -> Result<()> {
+        instructions::complete_task(ctx, task_id, reputation_amount, category_id, receipt)
+    }
+
+    /// Post a task with a token bounty held in escrow
+    pub fn post_task(
+        ctx: Context<PostTask>,
+        task_id: String,
+        bounty: u64,
+        reputation_amount: u64,
+        category_id: u32,
+        deadline: i64,
+    ) -> Result<()> {
+        instructions::post_task(ctx, task_id, bounty, reputation_amount, category_id, deadline)
+    }
+
+    /// Take on an open task
+    pub fn accept_task(ctx: Context<AcceptTask>) -> Result<()> {
+        instructions::accept_task(ctx)
+    }
+
+    /// Submit the result hash for an accepted task
+    pub fn submit_task(ctx: Context<SubmitTask>, result_hash: [u8; 32]) -> Result<()> {
+        instructions::submit_task(ctx, result_hash)
+    }
+
+    /// Release the bounty and mint reputation for a submitted task
+    pub fn approve_task(ctx: Context<ApproveTask>) -> Result<()> {
+        instructions::approve_task(ctx)
+    }
+
+    /// Reject a submission and open a dispute
+    pub fn reject_task(ctx: Context<RejectTask>) -> Result<()> {
+        instructions::reject_task(ctx)
+    }
+
+    /// Set the reputation tier thresholds
+    pub fn set_tiers(ctx: Context<SetTiers>, thresholds: Vec<TierThreshold>) -> Result<()> {
+        instructions::set_tiers(ctx, thresholds)
+    }
+
+    /// Recompute an agent's cached tier
+    pub fn refresh_tier(ctx: Context<RefreshTier>) -> Result<()> {
+        instructions::refresh_tier(ctx)
+    }
+
+    /// Set up jury parameters, the juror pool and its stake vault
+    #[allow(clippy::too_many_arguments)]
+    pub fn initialize_jury_config(
+        ctx: Context<InitializeJuryConfig>,
+        min_juror_reputation: u64,
+        min_juror_stake: u64,
+        jury_size: u8,
+        commit_period: i64,
+        reveal_period: i64,
+        juror_reward: u64,
+        non_reveal_penalty: u64,
+        juror_exit_delay: i64,
+        appeal_window: i64,
+        appeal_bond: u64,
+        dispute_bond: u64,
+        summon_period: i64,
     ) -> Result<()> {
-        instructions::complete_task(ctx, task_id, reputation_amount)
+        instructions::initialize_jury_config(
+            ctx,
+            min_juror_reputation,
+            min_juror_stake,
+            jury_size,
+            commit_period,
+            reveal_period,
+            juror_reward,
+            non_reveal_penalty,
+            juror_exit_delay,
+            appeal_window,
+            appeal_bond,
+            dispute_bond,
+            summon_period,
+        )
+    }
+
+    /// Stake to join the juror pool
+    pub fn register_juror(ctx: Context<RegisterJuror>, stake: u64) -> Result<()> {
+        instructions::register_juror(ctx, stake)
+    }
+
+    /// Leave the juror pool
+    pub fn request_juror_exit(ctx: Context<RequestJurorExit>) -> Result<()> {
+        instructions::request_juror_exit(ctx)
+    }
+
+    /// Withdraw juror stake after the exit delay
+    pub fn withdraw_juror_stake(ctx: Context<WithdrawJurorStake>) -> Result<()> {
+        instructions::withdraw_juror_stake(ctx)
+    }
+
+    /// Escalate a negative vouch into a dispute
+    pub fn open_vouch_dispute(ctx: Context<OpenVouchDispute>) -> Result<()> {
+        instructions::open_vouch_dispute(ctx)
+    }
+
+    /// Draw a jury for a dispute
+    pub fn summon_jury<'info>(ctx: Context<'_, '_, 'info, 'info, SummonJury<'info>>) -> Result<()> {
+        instructions::summon_jury(ctx)
+    }
+
+    /// Commit a hidden jury vote
+    pub fn commit_jury_vote(ctx: Context<CommitJuryVote>, commitment: [u8; 32]) -> Result<()> {
+        instructions::commit_jury_vote(ctx, commitment)
+    }
+
+    /// Reveal a committed jury vote
+    pub fn reveal_jury_vote(ctx: Context<RevealJuryVote>, agent_wins: bool, salt: [u8; 32]) -> Result<()> {
+        instructions::reveal_jury_vote(ctx, agent_wins, salt)
+    }
+
+    /// Tally a jury, settle juror stakes and resolve the dispute
+    pub fn finalize_jury<'info>(ctx: Context<'_, '_, 'info, 'info, FinalizeJury<'info>>) -> Result<()> {
+        instructions::finalize_jury(ctx)
+    }
+
+    /// Appeal a jury verdict with an escalating bond
+    pub fn appeal_dispute(ctx: Context<AppealDispute>) -> Result<()> {
+        instructions::appeal_dispute(ctx)
+    }
+
+    /// Return or forfeit the bond once an appeal jury has ruled
+    pub fn settle_appeal(ctx: Context<SettleAppeal>) -> Result<()> {
+        instructions::settle_appeal(ctx)
+    }
+
+    /// Restore the appealed verdict when no appeal jury was seated in time
+    pub fn lapse_appeal(ctx: Context<LapseAppeal>) -> Result<()> {
+        instructions::lapse_appeal(ctx)
+    }
+
+    /// Return or forfeit the challenger's dispute bond after a final verdict
+    pub fn settle_dispute_bond(ctx: Context<SettleDisputeBond>) -> Result<()> {
+        instructions::settle_dispute_bond(ctx)
+    }
+
+    /// Unlock the agent's vouch stake after a final verdict
+    pub fn close_dispute(ctx: Context<CloseDispute>) -> Result<()> {
+        instructions::close_dispute(ctx)
+    }
+
+    /// Close a dispute no jury was seated for in time
+    pub fn void_dispute(ctx: Context<VoidDispute>) -> Result<()> {
+        instructions::void_dispute(ctx)
+    }
+
+    /// Expire an accepted task past its deadline, refunding the bounty
+    pub fn expire_task(ctx: Context<ExpireTask>) -> Result<()> {
+        instructions::expire_task(ctx)
+    }
+
+    /// Return a task's bounty to the requester
+    pub fn refund_task(ctx: Context<RefundTask>) -> Result<()> {
+        instructions::refund_task(ctx)
+    }
+
+    /// Rate a completed task from 1 to 5
+    pub fn rate_task(ctx: Context<RateTask>, rating: u8, review_hash: Option<[u8; 32]>) -> Result<()> {
+        instructions::rate_task(ctx, rating, review_hash)
     }
 
     /// Vouch for another agent (stake tokens)
-    pub fn vouch_for(ctx: Context<VouchFor>, amount: u64) -> Result<()> {
-        instructions::vouch_for(ctx, amount)
+    pub fn vouch_for(ctx: Context<VouchFor>, amount: u64, expires_at: Option<i64>) -> Result<()> {
+        instructions::vouch_for(ctx, amount, expires_at)
     }
 
     /// Challenge/vouch against another agent
-    pub fn vouch_against(ctx: Context<VouchAgainst>, amount: u64) -> Result<()> {
-        instructions::vouch_against(ctx, amount)
+    pub fn vouch_against(ctx: Context<VouchAgainst>, amount: u64, expires_at: Option<i64>) -> Result<()> {
+        instructions::vouch_against(ctx, amount, expires_at)
     }
 
     /// Withdraw vouch (unstake)
@@ -49,13 +238,176 @@ pub mod agentreputation_dao {
         instructions::withdraw_vouch(ctx)
     }
 
+    /// Top up an existing vouch; only the added amount gets a new lockup
+    pub fn increase_vouch(ctx: Context<IncreaseVouch>, amount: u64) -> Result<()> {
+        instructions::increase_vouch(ctx, amount)
+    }
+
+    /// Partially withdraw unlocked stake, keeping the vouch open
+    pub fn decrease_vouch(ctx: Context<DecreaseVouch>, amount: u64) -> Result<()> {
+        instructions::decrease_vouch(ctx, amount)
+    }
+
+    /// Flip a vouch between for and against without closing it
+    pub fn revise_vouch(ctx: Context<ReviseVouch>) -> Result<()> {
+        instructions::revise_vouch(ctx)
+    }
+
+    /// Close an expired vouch and return its escrow to the voucher
+    pub fn expire_vouch(ctx: Context<ExpireVouch>) -> Result<()> {
+        instructions::expire_vouch(ctx)
+    }
+
+    /// Extend a vouch's expiry
+    pub fn renew_vouch(ctx: Context<RenewVouch>, expires_at: Option<i64>) -> Result<()> {
+        instructions::renew_vouch(ctx, expires_at)
+    }
+
+    /// Slash a vouch's escrowed stake into the treasury, by the authority or after a jury verdict
+    pub fn slash_vouch(ctx: Context<SlashVouch>) -> Result<()> {
+        instructions::slash_vouch(ctx)
+    }
+
+    /// Create the vault that holds voucher rewards
+    pub fn initialize_reward_vault(ctx: Context<InitializeRewardVault>) -> Result<()> {
+        instructions::initialize_reward_vault(ctx)
+    }
+
+    /// Claim rewards accrued by a positive vouch
+    pub fn claim_vouch_rewards(ctx: Context<ClaimVouchRewards>) -> Result<()> {
+        instructions::claim_vouch_rewards(ctx)
+    }
+
+    /// Recompute a profile's vouch counters from its live vouch records
+    pub fn audit_vouch_aggregates<'info>(
+        ctx: Context<'_, '_, 'info, 'info, AuditVouchAggregates<'info>>,
+    ) -> Result<()> {
+        instructions::audit_vouch_aggregates(ctx)
+    }
+
     /// Apply decay to inactive agent
     pub fn apply_decay(ctx: Context<ApplyDecay>) -> Result<()> {
         instructions::apply_decay(ctx)
     }
 
-    /// Get agent reputation score
-    pub fn get_reputation(ctx: Context<GetReputation>) -> Result<u64> {
+    /// Apply decay to many agents at once, paying the keeper from the treasury
+    pub fn apply_decay_batch<'info>(ctx: Context<'_, '_, 'info, 'info, ApplyDecayBatch<'info>>) -> Result<()> {
+        instructions::apply_decay_batch(ctx)
+    }
+
+    /// Apply decay to one of an agent's domain scores
+    pub fn apply_domain_decay(ctx: Context<ApplyDomainDecay>) -> Result<()> {
+        instructions::apply_domain_decay(ctx)
+    }
+
+    /// Get agent reputation score and any domain scores passed in remaining accounts
+    pub fn get_reputation<'info>(ctx: Context<'_, '_, 'info, 'info, GetReputation<'info>>) -> Result<ReputationView> {
         instructions::get_reputation(ctx)
     }
+
+    /// Get the score an agent held at a past timestamp
+    pub fn get_reputation_at(ctx: Context<GetReputation>, timestamp: i64) -> Result<u64> {
+        instructions::get_reputation_at(ctx, timestamp)
+    }
+
+    /// Register a skill category
+    pub fn create_category(ctx: Context<CreateCategory>, category_id: u32, name: String) -> Result<()> {
+        instructions::create_category(ctx, category_id, name)
+    }
+
+    /// Enable or retire a skill category
+    pub fn set_category_active(ctx: Context<SetCategoryActive>, is_active: bool) -> Result<()> {
+        instructions::set_category_active(ctx, is_active)
+    }
+
+    /// Register an off-chain client key allowed to sign task receipts
+    pub fn register_client_key(ctx: Context<RegisterClientKey>, key: Pubkey) -> Result<()> {
+        instructions::register_client_key(ctx, key)
+    }
+
+    /// Enable or revoke a client key
+    pub fn set_client_key_active(ctx: Context<SetClientKeyActive>, is_active: bool) -> Result<()> {
+        instructions::set_client_key_active(ctx, is_active)
+    }
+
+    /// Create the governance timelock
+    pub fn initialize_timelock(
+        ctx: Context<InitializeTimelock>,
+        delay: i64,
+        voting_period: i64,
+        quorum: u64,
+        proposal_threshold: u64,
+    ) -> Result<()> {
+        instructions::initialize_timelock(ctx, delay, voting_period, quorum, proposal_threshold)
+    }
+
+    /// Propose a config change
+    pub fn create_proposal(ctx: Context<CreateProposal>, change: ConfigChange, description_hash: [u8; 32]) -> Result<()> {
+        instructions::create_proposal(ctx, change, description_hash)
+    }
+
+    /// Vote on an active proposal
+    pub fn cast_vote<'info>(ctx: Context<'_, '_, 'info, 'info, CastVote<'info>>, support: bool) -> Result<()> {
+        instructions::cast_vote(ctx, support)
+    }
+
+    /// Delegate voting power to another agent
+    pub fn delegate_votes(ctx: Context<DelegateVotes>) -> Result<()> {
+        instructions::delegate_votes(ctx)
+    }
+
+    /// Revoke a vote delegation
+    pub fn revoke_delegation(ctx: Context<RevokeDelegation>) -> Result<()> {
+        instructions::revoke_delegation(ctx)
+    }
+
+    /// Close voting and queue a passed proposal in the timelock
+    pub fn queue_proposal(ctx: Context<QueueProposal>) -> Result<()> {
+        instructions::queue_proposal(ctx)
+    }
+
+    /// Execute a queued proposal after its timelock delay
+    pub fn execute_proposal(ctx: Context<ExecuteProposal>) -> Result<()> {
+        instructions::execute_proposal(ctx)
+    }
+
+    /// Cancel an active or queued proposal (guardian or authority)
+    pub fn cancel_proposal(ctx: Context<CancelProposal>) -> Result<()> {
+        instructions::cancel_proposal(ctx)
+    }
+
+    /// Create the treasury and its token vault
+    pub fn initialize_treasury(ctx: Context<InitializeTreasury>) -> Result<()> {
+        instructions::initialize_treasury(ctx)
+    }
+
+    /// Withdraw reputation tokens from the treasury vault
+    pub fn withdraw_treasury(ctx: Context<WithdrawTreasury>, amount: u64) -> Result<()> {
+        instructions::withdraw_treasury(ctx, amount)
+    }
+
+    /// Withdraw SOL fees held by the treasury
+    pub fn withdraw_treasury_sol(ctx: Context<WithdrawTreasurySol>, lamports: u64) -> Result<()> {
+        instructions::withdraw_treasury_sol(ctx, lamports)
+    }
+
+    /// Publish a merkle root over agent scores for an epoch
+    pub fn publish_snapshot(
+        ctx: Context<PublishSnapshot>,
+        epoch: u64,
+        merkle_root: [u8; 32],
+        agent_count: u64,
+    ) -> Result<()> {
+        instructions::publish_snapshot(ctx, epoch, merkle_root, agent_count)
+    }
+
+    /// Verify an agent's score against a published snapshot
+    pub fn verify_snapshot_proof(
+        ctx: Context<VerifySnapshotProof>,
+        agent: Pubkey,
+        score: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        instructions::verify_snapshot_proof(ctx, agent, score, proof)
+    }
 }
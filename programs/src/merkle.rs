@@ -0,0 +1,24 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak::hashv;
+
+/// Domain separators so a leaf can never be passed off as an inner node
+const LEAF_PREFIX: &[u8] = &[0];
+const NODE_PREFIX: &[u8] = &[1];
+
+/// Leaf hash for one (agent, score) entry of a reputation snapshot
+pub fn snapshot_leaf(agent: &Pubkey, score: u64) -> [u8; 32] {
+    hashv(&[LEAF_PREFIX, agent.as_ref(), &score.to_le_bytes()]).to_bytes()
+}
+
+/// Hash two sibling nodes. Pairs are sorted first so proofs don't need to
+/// carry left/right flags.
+pub fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (lo, hi) = if a <= b { (a, b) } else { (b, a) };
+    hashv(&[NODE_PREFIX, lo, hi]).to_bytes()
+}
+
+/// Walk an inclusion proof from `leaf` up and compare against `root`
+pub fn verify_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: [u8; 32]) -> bool {
+    let computed = proof.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling));
+    computed == *root
+}
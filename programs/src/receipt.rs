@@ -0,0 +1,81 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::instruction::Instruction;
+
+/// Domain separator so a receipt signature can't be replayed as any other message
+const RECEIPT_PREFIX: &[u8] = b"agentreputation:receipt:v1";
+
+/// Layout of the Ed25519 precompile instruction data
+const SIGNATURE_OFFSETS_START: usize = 2;
+const SIGNATURE_OFFSETS_SIZE: usize = 14;
+const PUBKEY_SIZE: usize = 32;
+const SIGNATURE_SIZE: usize = 64;
+
+/// Receipts older than this can't be redeemed
+pub const MAX_RECEIPT_AGE: i64 = 7 * 86400;
+
+/// Off-chain completion receipt passed to `complete_task`. The client signs
+/// `receipt_message` with its registered key.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TaskReceipt {
+    pub client: Pubkey,
+    pub timestamp: i64,
+    pub nonce: u64,
+}
+
+/// Bytes the client signs for a receipt
+pub fn receipt_message(
+    task_id: &str,
+    agent: &Pubkey,
+    reputation_amount: u64,
+    category_id: u32,
+    timestamp: i64,
+    nonce: u64,
+) -> Vec<u8> {
+    let mut message = Vec::with_capacity(RECEIPT_PREFIX.len() + 32 + 8 + 4 + 8 + 8 + task_id.len());
+    message.extend_from_slice(RECEIPT_PREFIX);
+    message.extend_from_slice(agent.as_ref());
+    message.extend_from_slice(&reputation_amount.to_le_bytes());
+    message.extend_from_slice(&category_id.to_le_bytes());
+    message.extend_from_slice(&timestamp.to_le_bytes());
+    message.extend_from_slice(&nonce.to_le_bytes());
+    message.extend_from_slice(task_id.as_bytes());
+    message
+}
+
+/// Check that `ix` is an Ed25519 precompile instruction verifying exactly one
+/// signature by `signer` over `message`. The precompile has already checked
+/// the signature itself, or the transaction would have failed.
+pub fn is_ed25519_verification(ix: &Instruction, signer: &Pubkey, message: &[u8]) -> bool {
+    if ix.program_id != ed25519_program::ID || !ix.accounts.is_empty() {
+        return false;
+    }
+
+    let data = &ix.data;
+    if data.len() < SIGNATURE_OFFSETS_START + SIGNATURE_OFFSETS_SIZE || data[0] != 1 {
+        return false;
+    }
+
+    let read_u16 = |at: usize| u16::from_le_bytes([data[at], data[at + 1]]);
+    let offsets = SIGNATURE_OFFSETS_START;
+    let signature_offset = read_u16(offsets) as usize;
+    let signature_ix = read_u16(offsets + 2);
+    let pubkey_offset = read_u16(offsets + 4) as usize;
+    let pubkey_ix = read_u16(offsets + 6);
+    let message_offset = read_u16(offsets + 8) as usize;
+    let message_size = read_u16(offsets + 10) as usize;
+    let message_ix = read_u16(offsets + 12);
+
+    // Everything must live in the precompile instruction's own data, otherwise
+    // the checked bytes could differ from the ones we read here
+    if [signature_ix, pubkey_ix, message_ix].iter().any(|&i| i != u16::MAX) {
+        return false;
+    }
+    if signature_offset + SIGNATURE_SIZE > data.len() {
+        return false;
+    }
+
+    let pubkey = data.get(pubkey_offset..pubkey_offset + PUBKEY_SIZE);
+    let signed = data.get(message_offset..message_offset + message_size);
+    pubkey == Some(signer.as_ref()) && signed == Some(message)
+}
@@ -1,22 +1,174 @@
 use anchor_lang::prelude::*;
+use anchor_lang::solana_program::keccak::hashv;
+use crate::errors::ReputationError;
 
 /// Protocol configuration
 #[account]
+#[derive(Default)]
 pub struct ProtocolConfig {
     pub authority: Pubkey,
     pub reputation_mint: Pubkey,
     pub min_reputation_for_vouching: u64,
     pub decay_rate_per_day: u64, // basis points (10000 = 100%)
     pub vouch_lockup_period: i64, // seconds
+    pub vouch_fee_bps: u64, // taken from the stake on vouch_for
+    pub dispute_fee_bps: u64, // taken on vouch_against
+    pub registration_fee: u64, // lamports, flat
+    pub registration_bond: u64, // lamports, refundable on deregister
+    pub min_registration_tenure: i64, // seconds before the bond can be reclaimed
+    pub voucher_reward_bps: u64, // share of task mints paid to the agent's vouchers
+    pub min_vouch_duration: i64, // seconds; shortest expiry a voucher may pick
+    pub max_vouch_duration: i64, // seconds; 0 = open-ended vouches allowed
+    pub missed_deadline_penalty: u64, // reputation taken when an accepted task expires
+    pub mint_epoch_duration: i64, // seconds per minting epoch
+    pub global_mint_cap: u64, // max reputation minted per epoch, 0 = uncapped
+    pub agent_mint_cap: u64, // max reputation minted per agent per epoch, 0 = uncapped
+    pub mint_epoch: i64, // epoch `epoch_minted` refers to
+    pub epoch_minted: u64,
+    pub decay_keeper_reward: u64, // paid from the treasury per profile decayed by `apply_decay_batch`
+    pub decay_grace_days: i64, // inactive days before decay starts
+    pub reputation_floor: u64, // decay never takes a score below this
+    pub floor_per_task: u64, // added to the floor per lifetime task completed
+    pub max_reputation_floor: u64, // cap on the task-scaled floor
+    pub guardian: Pubkey, // can pause, but only the authority can unpause
+    pub paused: u8, // bitmask of PAUSE_* groups
     pub bump: u8,
 }
 
+/// Instruction groups that can be paused independently
+pub const PAUSE_MINTING: u8 = 1 << 0;
+pub const PAUSE_VOUCHING: u8 = 1 << 1;
+pub const PAUSE_WITHDRAWALS: u8 = 1 << 2;
+pub const PAUSE_GOVERNANCE: u8 = 1 << 3;
+pub const PAUSE_ALL: u8 = PAUSE_MINTING | PAUSE_VOUCHING | PAUSE_WITHDRAWALS | PAUSE_GOVERNANCE;
+
+/// Most `apply_decay_batch` may pay per decayed profile, so a config change
+/// can't turn the keeper reward into a treasury drain
+pub const MAX_DECAY_KEEPER_REWARD: u64 = 1_000;
+
 impl ProtocolConfig {
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 1;
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + 1 + 1;
+
+    /// Fail if any of `groups` is paused
+    pub fn require_not_paused(&self, groups: u8) -> Result<()> {
+        require!(self.paused & groups == 0, ReputationError::ProtocolPaused);
+        Ok(())
+    }
+
+    /// Retune the per-epoch mint caps. Changing the epoch length restarts
+    /// the current epoch's counters.
+    pub fn update_mint_caps(&mut self, mint_epoch_duration: i64, global_mint_cap: u64, agent_mint_cap: u64, now: i64) -> Result<()> {
+        require!(mint_epoch_duration > 0, ReputationError::InvalidMintCap);
+
+        if self.mint_epoch_duration != mint_epoch_duration {
+            self.mint_epoch_duration = mint_epoch_duration;
+            self.mint_epoch = self.current_mint_epoch(now);
+            self.epoch_minted = 0;
+        }
+        self.global_mint_cap = global_mint_cap;
+        self.agent_mint_cap = agent_mint_cap;
+        Ok(())
+    }
+
+    pub fn update_decay_policy(
+        &mut self,
+        decay_grace_days: i64,
+        reputation_floor: u64,
+        floor_per_task: u64,
+        max_reputation_floor: u64,
+    ) -> Result<()> {
+        require!(decay_grace_days >= 0, ReputationError::InvalidDecayPolicy);
+        require!(max_reputation_floor >= reputation_floor, ReputationError::InvalidDecayPolicy);
+
+        self.decay_grace_days = decay_grace_days;
+        self.reputation_floor = reputation_floor;
+        self.floor_per_task = floor_per_task;
+        self.max_reputation_floor = max_reputation_floor;
+        Ok(())
+    }
+
+    /// Index of the minting epoch containing `now`
+    pub fn current_mint_epoch(&self, now: i64) -> i64 {
+        if self.mint_epoch_duration > 0 {
+            now / self.mint_epoch_duration
+        } else {
+            0
+        }
+    }
+
+    /// Count `amount` against the global and the agent's per-epoch mint caps
+    pub fn record_mint(&mut self, profile: &mut AgentProfile, amount: u64, now: i64) -> Result<()> {
+        let epoch = self.current_mint_epoch(now);
+        if self.mint_epoch != epoch {
+            self.mint_epoch = epoch;
+            self.epoch_minted = 0;
+        }
+        if profile.mint_epoch != epoch {
+            profile.mint_epoch = epoch;
+            profile.epoch_minted = 0;
+        }
+
+        let global_minted = self.epoch_minted.checked_add(amount).ok_or(ReputationError::MathOverflow)?;
+        let agent_minted = profile.epoch_minted.checked_add(amount).ok_or(ReputationError::MathOverflow)?;
+        require!(
+            self.global_mint_cap == 0 || global_minted <= self.global_mint_cap,
+            ReputationError::GlobalMintCapExceeded
+        );
+        require!(
+            self.agent_mint_cap == 0 || agent_minted <= self.agent_mint_cap,
+            ReputationError::AgentMintCapExceeded
+        );
+
+        self.epoch_minted = global_minted;
+        profile.epoch_minted = agent_minted;
+        Ok(())
+    }
+
+    /// Check a voucher-chosen expiry against the configured bounds.
+    /// Returns the value to store, with 0 meaning "never expires".
+    pub fn validate_vouch_expiry(&self, expires_at: Option<i64>, now: i64) -> Result<i64> {
+        match expires_at {
+            None => {
+                require!(self.max_vouch_duration == 0, ReputationError::InvalidVouchExpiry);
+                Ok(0)
+            }
+            Some(expires_at) => {
+                let duration = expires_at.saturating_sub(now);
+                require!(
+                    duration >= self.min_vouch_duration.max(self.vouch_lockup_period),
+                    ReputationError::InvalidVouchExpiry
+                );
+                require!(
+                    self.max_vouch_duration == 0 || duration <= self.max_vouch_duration,
+                    ReputationError::InvalidVouchExpiry
+                );
+                Ok(expires_at)
+            }
+        }
+    }
+}
+
+/// Protocol treasury - PDA seeded by [treasury]
+///
+/// Holds SOL fees directly and owns the reputation token vault
+/// (PDA seeded by [treasury_vault]) that receives token fees and slashed stake.
+#[account]
+pub struct Treasury {
+    pub vault: Pubkey,
+    pub total_token_fees: u64,
+    pub total_sol_fees: u64,
+    pub total_slashed: u64,
+    pub total_sol_slashed: u64,
+    pub bump: u8,
+}
+
+impl Treasury {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 8 + 1;
 }
 
 /// Agent profile - PDA seeded by [agent, agent_pubkey]
 #[account]
+#[derive(Default)]
 pub struct AgentProfile {
     pub owner: Pubkey,
     pub name: String, // max 50 chars
@@ -27,40 +179,921 @@ pub struct AgentProfile {
     pub positive_vouches: u64,
     pub negative_vouches: u64,
     pub staked_amount: u64,
+    pub total_vouch_stake: u64, // escrowed stake backing this agent
+    pub reward_per_share: u128, // scaled by REWARD_PRECISION
+    pub vouch_ramps: [VouchRamp; MAX_VOUCH_RAMPS], // vouches still maturing
+    pub rating_count: u64,
+    pub rating_total: u64, // sum of 1-5 ratings
+    pub mint_epoch: i64, // epoch `epoch_minted` refers to
+    pub epoch_minted: u64,
+    pub open_disputes: u32, // vouch stake backing this agent is locked while non-zero
+    pub tier: u8, // cached from TierConfig, 0 = no tier
+    pub checkpoints: [ScoreCheckpoint; MAX_SCORE_CHECKPOINTS], // ring buffer of past scores
+    pub checkpoint_head: u8, // slot the next checkpoint goes into
+    pub checkpoint_count: u8,
     pub bump: u8,
 }
 
 impl AgentProfile {
-    pub const LEN: usize = 8 + 32 + (4 + 50) + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 1;
+    pub const LEN: usize = 8 + 32 + (4 + 50) + 8 + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 16 + (VouchRamp::LEN * MAX_VOUCH_RAMPS) + 8 + 8 + 8 + 8 + 4 + 1 + (ScoreCheckpoint::LEN * MAX_SCORE_CHECKPOINTS) + 1 + 1 + 1;
+
+    /// Average task rating scaled by 100 (e.g. 450 = 4.5 stars), 0 if unrated
+    pub fn average_rating(&self) -> u64 {
+        if self.rating_count == 0 {
+            return 0;
+        }
+        self.rating_total.saturating_mul(100) / self.rating_count
+    }
+
+    /// Take a closing vouch out of this (target) profile's aggregates
+    pub fn remove_vouch(&mut self, vouch: &VouchRecord, now: i64, lockup_period: i64) {
+        if vouch.is_positive {
+            self.positive_vouches = self.positive_vouches.saturating_sub(1);
+            self.total_vouch_stake = self.total_vouch_stake.saturating_sub(vouch.amount);
+        } else {
+            self.negative_vouches = self.negative_vouches.saturating_sub(1);
+        }
+        self.remove_vouch_ramp(vouch.is_positive, vouch.created_at, now, lockup_period);
+    }
+
+    /// Start the influence ramp for a vouch created at `now`. Matured ramps
+    /// are dropped first; if every slot is still maturing, the newest slot
+    /// absorbs this vouch and restarts, which can only understate influence.
+    pub fn push_vouch_ramp(&mut self, is_positive: bool, now: i64, lockup_period: i64) {
+        for ramp in self.vouch_ramps.iter_mut() {
+            if !ramp.is_empty() && now.saturating_sub(ramp.started_at) >= lockup_period {
+                *ramp = VouchRamp::default();
+            }
+        }
+
+        let slot = match self.vouch_ramps.iter().position(|r| r.is_empty()) {
+            Some(free) => free,
+            None => self
+                .vouch_ramps
+                .iter()
+                .enumerate()
+                .max_by_key(|(_, r)| r.started_at)
+                .map(|(i, _)| i)
+                .unwrap_or(0),
+        };
+
+        let ramp = &mut self.vouch_ramps[slot];
+        ramp.started_at = now;
+        if is_positive {
+            ramp.positive = ramp.positive.saturating_add(1);
+        } else {
+            ramp.negative = ramp.negative.saturating_add(1);
+        }
+    }
+
+    /// Take a vouch created at `created_at` out of its ramp. A matured vouch
+    /// has already left the ramps, and must not be taken from a younger one.
+    pub fn remove_vouch_ramp(&mut self, is_positive: bool, created_at: i64, now: i64, lockup_period: i64) {
+        if now.saturating_sub(created_at) >= lockup_period {
+            return;
+        }
+
+        // The vouch sits in the oldest ramp that started at or after it
+        let slot = self
+            .vouch_ramps
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| r.started_at >= created_at)
+            .filter(|(_, r)| if is_positive { r.positive > 0 } else { r.negative > 0 })
+            .min_by_key(|(_, r)| r.started_at)
+            .map(|(i, _)| i);
+
+        if let Some(slot) = slot {
+            let ramp = &mut self.vouch_ramps[slot];
+            if is_positive {
+                ramp.positive -= 1;
+            } else {
+                ramp.negative -= 1;
+            }
+            if ramp.positive == 0 && ramp.negative == 0 {
+                *ramp = VouchRamp::default();
+            }
+        }
+    }
+
+    /// Set the score and checkpoint it. Several changes within the same
+    /// second share one checkpoint.
+    pub fn set_reputation_score(&mut self, score: u64, now: i64) {
+        self.reputation_score = score;
+
+        if self.checkpoint_count > 0 {
+            let latest = self.checkpoint_at(self.checkpoint_count as usize - 1);
+            if self.checkpoints[latest].timestamp >= now {
+                self.checkpoints[latest].score = score;
+                return;
+            }
+        }
+
+        self.checkpoints[self.checkpoint_head as usize] = ScoreCheckpoint { timestamp: now, score };
+        self.checkpoint_head = ((self.checkpoint_head as usize + 1) % MAX_SCORE_CHECKPOINTS) as u8;
+        if (self.checkpoint_count as usize) < MAX_SCORE_CHECKPOINTS {
+            self.checkpoint_count += 1;
+        }
+    }
+
+    /// Stored score held at `timestamp`, from the checkpoint history. Decay
+    /// that hadn't been applied by then isn't included. `None` if the
+    /// history has wrapped and no longer reaches back that far.
+    pub fn reputation_at(&self, timestamp: i64) -> Option<u64> {
+        let count = self.checkpoint_count as usize;
+
+        // Binary search for the first checkpoint after `timestamp`, oldest first
+        let (mut low, mut high) = (0, count);
+        while low < high {
+            let mid = (low + high) / 2;
+            if self.checkpoints[self.checkpoint_at(mid)].timestamp <= timestamp {
+                low = mid + 1;
+            } else {
+                high = mid;
+            }
+        }
+
+        if low > 0 {
+            Some(self.checkpoints[self.checkpoint_at(low - 1)].score)
+        } else if count < MAX_SCORE_CHECKPOINTS {
+            // Nothing has been overwritten, so this predates the agent
+            Some(0)
+        } else {
+            None
+        }
+    }
+
+    /// Buffer index of the `i`th oldest checkpoint
+    fn checkpoint_at(&self, i: usize) -> usize {
+        let oldest = (self.checkpoint_head as usize + MAX_SCORE_CHECKPOINTS - self.checkpoint_count as usize)
+            % MAX_SCORE_CHECKPOINTS;
+        (oldest + i) % MAX_SCORE_CHECKPOINTS
+    }
+
+    /// Positive and negative vouch counts weighted by maturity, in basis
+    /// points of a fully matured vouch
+    pub fn weighted_vouches(&self, now: i64, lockup_period: i64) -> (u64, u64) {
+        let mut positive = self.positive_vouches.saturating_mul(10000);
+        let mut negative = self.negative_vouches.saturating_mul(10000);
+
+        for ramp in self.vouch_ramps.iter().filter(|r| !r.is_empty()) {
+            let missing = 10000 - calculate_vouch_weight(ramp.started_at, now, lockup_period);
+            positive = positive.saturating_sub((ramp.positive as u64) * missing);
+            negative = negative.saturating_sub((ramp.negative as u64) * missing);
+        }
+        (positive, negative)
+    }
+}
+
+/// Score checkpoints kept per profile
+pub const MAX_SCORE_CHECKPOINTS: usize = 32;
+
+/// Score an agent held from `timestamp` until the next checkpoint
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct ScoreCheckpoint {
+    pub timestamp: i64,
+    pub score: u64,
+}
+
+impl ScoreCheckpoint {
+    pub const LEN: usize = 8 + 8;
+}
+
+/// Max maturing vouch groups tracked per profile
+pub const MAX_VOUCH_RAMPS: usize = 8;
+
+/// Vouches that started ramping up at the same time
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct VouchRamp {
+    pub started_at: i64,
+    pub positive: u32,
+    pub negative: u32,
+}
+
+impl VouchRamp {
+    pub const LEN: usize = 8 + 4 + 4;
+
+    pub fn is_empty(&self) -> bool {
+        self.positive == 0 && self.negative == 0
+    }
+}
+
+/// Registration bond - PDA seeded by [bond, agent_pubkey]
+///
+/// Holds the agent's bond in lamports on top of its own rent. Refunded by
+/// closing it on deregister, or closed into the treasury when slashed.
+#[account]
+pub struct RegistrationBond {
+    pub agent: Pubkey,
+    pub amount: u64,
+    pub deposited_at: i64,
+    pub bump: u8,
+}
+
+impl RegistrationBond {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 1;
 }
 
 /// Vouch record - PDA seeded by [vouch, voucher, vouched_for]
 #[account]
+#[derive(Default)]
 pub struct VouchRecord {
     pub voucher: Pubkey,
     pub vouched_for: Pubkey,
     pub amount: u64,
     pub is_positive: bool, // true = vouch for, false = vouch against
+    pub is_escrowed: bool, // amount is held in the vouch escrow
     pub created_at: i64,
+    pub reward_debt: u128, // rewards already accounted for at the current stake
+    pub tranches: [VouchTranche; MAX_VOUCH_TRANCHES], // sums to amount
+    pub tranche_count: u8,
+    pub expires_at: i64, // 0 = never
     pub bump: u8,
 }
 
 impl VouchRecord {
-    pub const LEN: usize = 8 + 32 + 32 + 8 + 1 + 8 + 1;
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 1 + 1 + 8 + 16 + (VouchTranche::LEN * MAX_VOUCH_TRANCHES) + 1 + 8 + 1;
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at != 0 && now >= self.expires_at
+    }
+
+    /// Reset the record to a single tranche holding `amount`
+    pub fn init_tranches(&mut self, amount: u64, now: i64) {
+        self.tranches = [VouchTranche::default(); MAX_VOUCH_TRANCHES];
+        self.tranches[0] = VouchTranche { amount, locked_at: now };
+        self.tranche_count = 1;
+    }
+
+    /// Add a top-up as its own tranche. When all slots are used the newest
+    /// tranche absorbs it, which only ever makes its lockup later.
+    pub fn add_tranche(&mut self, amount: u64, now: i64) {
+        let count = self.tranche_count as usize;
+        if count < MAX_VOUCH_TRANCHES {
+            self.tranches[count] = VouchTranche { amount, locked_at: now };
+            self.tranche_count += 1;
+        } else {
+            let last = &mut self.tranches[MAX_VOUCH_TRANCHES - 1];
+            last.amount = last.amount.saturating_add(amount);
+            last.locked_at = now;
+        }
+        self.amount = self.amount.saturating_add(amount);
+    }
+
+    /// Stake whose lockup has run out
+    pub fn unlocked_amount(&self, now: i64, lockup_period: i64) -> u64 {
+        self.tranches[..self.tranche_count as usize]
+            .iter()
+            .filter(|t| now.saturating_sub(t.locked_at) >= lockup_period)
+            .fold(0u64, |sum, t| sum.saturating_add(t.amount))
+    }
+
+    pub fn is_fully_unlocked(&self, now: i64, lockup_period: i64) -> bool {
+        self.unlocked_amount(now, lockup_period) >= self.amount
+    }
+
+    /// Take `amount` out of unlocked tranches, oldest first, dropping any
+    /// tranche that is emptied. Caller checks `unlocked_amount` beforehand.
+    pub fn remove_unlocked(&mut self, amount: u64, now: i64, lockup_period: i64) {
+        let mut remaining = amount;
+        for tranche in self.tranches[..self.tranche_count as usize].iter_mut() {
+            if remaining == 0 {
+                break;
+            }
+            if now.saturating_sub(tranche.locked_at) >= lockup_period {
+                let taken = tranche.amount.min(remaining);
+                tranche.amount -= taken;
+                remaining -= taken;
+            }
+        }
+
+        let mut kept = [VouchTranche::default(); MAX_VOUCH_TRANCHES];
+        let mut count = 0;
+        for tranche in self.tranches[..self.tranche_count as usize].iter() {
+            if tranche.amount > 0 {
+                kept[count] = *tranche;
+                count += 1;
+            }
+        }
+        self.tranches = kept;
+        self.tranche_count = count as u8;
+        self.amount = self.amount.saturating_sub(amount - remaining);
+    }
+}
+
+/// Max separately-locked top-ups tracked per vouch
+pub const MAX_VOUCH_TRANCHES: usize = 8;
+
+/// Portion of a vouch's stake with its own lockup clock
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct VouchTranche {
+    pub amount: u64,
+    pub locked_at: i64,
+}
+
+impl VouchTranche {
+    pub const LEN: usize = 8 + 8;
 }
 
-/// Task completion record
+/// Task completion record - PDA seeded by [task, task_id, agent] for
+/// self-reported tasks, or [posted_task_record, posted_task] for posted ones
 #[account]
 pub struct TaskRecord {
     pub agent: Pubkey,
     pub task_id: String, // max 100 chars
     pub reputation_earned: u64,
     pub completed_at: i64,
+    pub category_id: u32,
+    pub requester: Pubkey, // default for self-reported tasks
+    pub rating: u8, // 1-5, 0 = not rated yet
+    pub review_hash: [u8; 32],
+    pub missed_deadline: bool,
+    pub reputation_penalty: u64,
+    pub posted_task: Pubkey, // default for self-reported tasks
     pub bump: u8,
 }
 
 impl TaskRecord {
-    pub const LEN: usize = 8 + 32 + (4 + 100) + 8 + 8 + 1;
+    pub const LEN: usize = 8 + 32 + (4 + 100) + 8 + 8 + 4 + 32 + 1 + 32 + 1 + 8 + 32 + 1;
+
+    /// Whether `key` is this record's PDA, whichever way it was created
+    pub fn has_address(&self, key: &Pubkey, program_id: &Pubkey) -> bool {
+        let bump = [self.bump];
+        let expected = if self.posted_task == Pubkey::default() {
+            Pubkey::create_program_address(&[b"task", self.task_id.as_bytes(), self.agent.as_ref(), &bump], program_id)
+        } else {
+            Pubkey::create_program_address(&[b"posted_task_record", self.posted_task.as_ref(), &bump], program_id)
+        };
+        expected.is_ok_and(|expected| expected == *key)
+    }
+}
+
+/// Reputation multiplier per rating, in basis points. A 3-star rating leaves
+/// the minted amount unchanged.
+pub const RATING_MULTIPLIER_BPS: [u64; 5] = [5000, 7500, 10000, 12500, 15000];
+
+/// Reputation a task is worth after applying its rating
+pub fn calculate_rated_reputation(reputation_earned: u64, rating: u8) -> u64 {
+    let multiplier = RATING_MULTIPLIER_BPS[(rating.clamp(1, 5) - 1) as usize];
+    ((reputation_earned as u128) * (multiplier as u128) / 10000) as u64
+}
+
+/// Skill category - PDA seeded by [category, category_id]
+///
+/// Registry entry managed by the config authority. Tasks can only be
+/// completed against active categories.
+#[account]
+pub struct SkillCategory {
+    pub id: u32,
+    pub name: String, // max 32 chars
+    pub is_active: bool,
+    pub bump: u8,
+}
+
+impl SkillCategory {
+    pub const LEN: usize = 8 + 4 + (4 + 32) + 1 + 1;
+}
+
+/// Per-agent score in one skill category - PDA seeded by [domain, agent, category_id]
+#[account]
+pub struct DomainReputation {
+    pub agent: Pubkey,
+    pub category_id: u32,
+    pub score: u64,
+    pub tasks_completed: u64,
+    pub last_activity_timestamp: i64,
+    pub bump: u8,
+}
+
+impl DomainReputation {
+    pub const LEN: usize = 8 + 32 + 4 + 8 + 8 + 8 + 1;
+
+    /// Days of decay owed at `now` and the score once they are applied. The
+    /// floor scales with the tasks completed in this domain.
+    pub fn pending_decay(&self, now: i64, config: &ProtocolConfig) -> (i64, u64) {
+        let days = calculate_decay_days(self.last_activity_timestamp, now, config.decay_grace_days);
+        (days, calculate_profile_decay(self.score, days, self.tasks_completed, config))
+    }
+}
+
+/// Registered off-chain client key - PDA seeded by [client_key, key]
+///
+/// Receipts signed by an active client key can be redeemed through `complete_task`.
+#[account]
+pub struct ClientKey {
+    pub key: Pubkey,
+    pub is_active: bool,
+    pub registered_at: i64,
+    pub bump: u8,
+}
+
+impl ClientKey {
+    pub const LEN: usize = 8 + 32 + 1 + 8 + 1;
+}
+
+/// Lifecycle of a posted task
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum TaskStatus {
+    Open,
+    Accepted,
+    Submitted,
+    Disputed,
+    Completed,
+    Refunded,
+    Expired,
+}
+
+/// Task posted by a requester - PDA seeded by [posted_task, requester, task_id]
+///
+/// The bounty sits in an escrow token account owned by this PDA until the
+/// task is approved or refunded.
+#[account]
+pub struct PostedTask {
+    pub requester: Pubkey,
+    pub agent: Pubkey,
+    pub task_id: String, // max 32 chars, used as a seed
+    pub category_id: u32,
+    pub bounty: u64,
+    pub reputation_amount: u64,
+    pub escrow: Pubkey,
+    pub result_hash: [u8; 32],
+    pub status: TaskStatus,
+    pub posted_at: i64,
+    pub accepted_at: i64,
+    pub submitted_at: i64,
+    pub deadline: i64, // submission cutoff
+    pub bump: u8,
+}
+
+impl PostedTask {
+    pub const LEN: usize = 8 + 32 + 32 + (4 + 32) + 4 + 8 + 8 + 32 + 32 + 1 + 8 + 8 + 8 + 8 + 1;
+}
+
+pub const MAX_POSTED_TASK_ID_LEN: usize = 32;
+
+/// What a dispute is about
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DisputeKind {
+    Task, // requester rejected a submission
+    Vouch, // negative voucher challenges the agent
+}
+
+/// Dispute between a claimant and an agent - PDA seeded by [dispute, subject]
+///
+/// The subject is the posted task or the challenger's vouch record.
+#[account]
+pub struct Dispute {
+    pub subject: Pubkey,
+    pub kind: DisputeKind,
+    pub claimant: Pubkey,
+    pub agent: Pubkey,
+    pub opened_at: i64,
+    pub round: u8, // jury rounds seated so far
+    pub resolved: bool, // latest jury has ruled
+    pub agent_won: bool,
+    pub appealable_until: i64, // the verdict takes effect after this
+    pub appeal_count: u8,
+    pub appellant: Pubkey,
+    pub appeal_bond: u64, // held until the appeal round is settled
+    pub appealed_verdict: bool, // `agent_won` before the pending appeal
+    pub closed: bool,
+    pub claimant_bond: u64, // held until the verdict is final or the dispute is voided
+    pub summon_deadline: i64, // the next jury must be seated before this
+    pub seed_slot: u64, // the next jury is drawn from this slot's hash
+    pub pool: [Pubkey; MAX_JURORS], // candidates for the next jury, fixed when the round opens
+    pub pool_count: u8,
+    pub bump: u8,
+}
+
+impl Dispute {
+    pub const LEN: usize = 8 + 32 + 1 + 32 + 32 + 8 + 1 + 1 + 1 + 8 + 1 + 32 + 8 + 1 + 1 + 8 + 8 + 8 + (32 * MAX_JURORS) + 1 + 1;
+
+    /// Resolved and past the appeal window, so the verdict can be acted on
+    pub fn is_final(&self, now: i64) -> bool {
+        self.resolved && now >= self.appealable_until
+    }
+
+    /// Closed without a verdict because no jury was seated in time
+    pub fn is_voided(&self) -> bool {
+        self.closed && !self.resolved
+    }
+
+    /// Candidates the next jury is drawn from
+    pub fn pool(&self) -> &[Pubkey] {
+        &self.pool[..self.pool_count as usize]
+    }
+
+    /// Fix the candidates for the next jury: the current pool minus both
+    /// parties. Later joins and exits can't change who gets drawn.
+    pub fn snapshot_pool(&mut self, jury_config: &JuryConfig) {
+        self.pool = [Pubkey::default(); MAX_JURORS];
+        let mut count = 0;
+        for juror in jury_config.pool().iter().filter(|k| **k != self.claimant && **k != self.agent) {
+            self.pool[count] = *juror;
+            count += 1;
+        }
+        self.pool_count = count as u8;
+    }
+}
+
+/// Jury parameters and the juror pool - PDA seeded by [jury_config]
+#[account]
+#[derive(Default)]
+pub struct JuryConfig {
+    pub min_juror_reputation: u64,
+    pub min_juror_stake: u64,
+    pub jury_size: u8,
+    pub commit_period: i64, // seconds
+    pub reveal_period: i64, // seconds
+    pub juror_reward: u64, // paid from the treasury per majority vote
+    pub non_reveal_penalty: u64, // taken from the stake of jurors who don't reveal
+    pub juror_exit_delay: i64, // seconds between leaving the pool and withdrawing stake
+    pub appeal_window: i64, // seconds a verdict can be appealed
+    pub appeal_bond: u64, // first appeal's bond, doubled for each further appeal
+    pub dispute_bond: u64, // posted by the challenger to open a vouch dispute
+    pub summon_period: i64, // seconds to seat a jury once a dispute is opened or appealed
+    pub vault: Pubkey, // holds all juror stakes
+    pub jurors: [Pubkey; MAX_JURORS],
+    pub juror_count: u8,
+    pub bump: u8,
+}
+
+impl JuryConfig {
+    pub const LEN: usize = 8 + 8 + 8 + 1 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 8 + 32 + (32 * MAX_JURORS) + 1 + 1;
+
+    /// Jurors seated in `round` (1-based): each appeal more than doubles the jury
+    pub fn jury_size_for_round(&self, round: u8) -> usize {
+        let mut size = self.jury_size as usize;
+        for _ in 1..round {
+            size = size * 2 + 1;
+        }
+        size
+    }
+
+    /// Bond for the next appeal of a dispute that has been appealed `appeal_count` times
+    pub fn appeal_bond_for(&self, appeal_count: u8) -> u64 {
+        self.appeal_bond.saturating_mul(1u64 << appeal_count.min(63))
+    }
+
+    pub fn pool(&self) -> &[Pubkey] {
+        &self.jurors[..self.juror_count as usize]
+    }
+
+    pub fn add_juror(&mut self, juror: Pubkey) -> Result<()> {
+        require!((self.juror_count as usize) < MAX_JURORS, ReputationError::JurorPoolFull);
+        self.jurors[self.juror_count as usize] = juror;
+        self.juror_count += 1;
+        Ok(())
+    }
+
+    pub fn remove_juror(&mut self, juror: &Pubkey) {
+        if let Some(i) = self.pool().iter().position(|k| k == juror) {
+            let last = self.juror_count as usize - 1;
+            self.jurors.swap(i, last);
+            self.jurors[last] = Pubkey::default();
+            self.juror_count -= 1;
+        }
+    }
+}
+
+pub const MAX_JURORS: usize = 32;
+pub const MAX_JURY_SIZE: usize = 15;
+/// Slots between opening or appealing a dispute and the slot its jury is
+/// drawn from, so nobody knows the seed when the round starts
+pub const JURY_SEED_DELAY_SLOTS: u64 = 8;
+
+/// Staked juror - PDA seeded by [juror, agent]
+#[account]
+pub struct Juror {
+    pub agent: Pubkey,
+    pub stake: u64, // includes rewards, less penalties
+    pub registered_at: i64,
+    pub exit_requested_at: i64, // 0 while in the pool
+    pub cases_served: u64,
+    pub active_cases: u32, // seated on juries that haven't been finalized
+    pub bump: u8,
+}
+
+impl Juror {
+    pub const LEN: usize = 8 + 32 + 8 + 8 + 8 + 8 + 4 + 1;
+}
+
+/// Juror vote states in `Jury::votes`
+pub const VOTE_NONE: u8 = 0;
+pub const VOTE_AGENT: u8 = 1;
+pub const VOTE_CLAIMANT: u8 = 2;
+
+/// One jury round for a dispute - PDA seeded by [jury, dispute, round]
+#[account]
+pub struct Jury {
+    pub dispute: Pubkey,
+    pub round: u8,
+    pub seed: [u8; 32], // selection seed, derived from a recent slot hash
+    pub jurors: [Pubkey; MAX_JURY_SIZE],
+    pub juror_count: u8,
+    pub commitments: [[u8; 32]; MAX_JURY_SIZE],
+    pub votes: [u8; MAX_JURY_SIZE], // VOTE_* per juror
+    pub commit_deadline: i64,
+    pub reveal_deadline: i64,
+    pub finalized: bool,
+    pub bump: u8,
+}
+
+/// Commitment a juror submits before revealing `vote` (VOTE_AGENT or VOTE_CLAIMANT)
+pub fn jury_vote_commitment(jury: &Pubkey, juror: &Pubkey, vote: u8, salt: &[u8; 32]) -> [u8; 32] {
+    hashv(&[jury.as_ref(), juror.as_ref(), &[vote], salt]).to_bytes()
+}
+
+impl Jury {
+    pub const LEN: usize = 8 + 32 + 1 + 32 + (32 * MAX_JURY_SIZE) + 1 + (32 * MAX_JURY_SIZE) + MAX_JURY_SIZE + 8 + 8 + 1 + 1;
+
+    pub fn seat_of(&self, juror: &Pubkey) -> Option<usize> {
+        self.jurors[..self.juror_count as usize].iter().position(|k| k == juror)
+    }
+}
+
+/// Governance timelock - PDA seeded by [timelock]
+///
+/// Passed proposals wait here until their ETA, giving agents time to react
+/// (e.g. withdraw vouches) before a config change lands.
+#[account]
+#[derive(Default)]
+pub struct Timelock {
+    pub delay: i64, // seconds between queueing and execution
+    pub voting_period: i64, // seconds a proposal is open for votes
+    pub quorum: u64, // minimum vote weight in favour for a proposal to pass
+    pub proposal_threshold: u64, // effective score needed to create a proposal
+    pub proposal_count: u64,
+    pub queue: [QueuedProposal; MAX_QUEUED_PROPOSALS],
+    pub bump: u8,
+}
+
+impl Timelock {
+    pub const LEN: usize = 8 + 8 + 8 + 8 + 8 + 8 + (QueuedProposal::LEN * MAX_QUEUED_PROPOSALS) + 1;
+
+    /// Put a proposal in the first free queue slot, after dropping any
+    /// entries that are past their grace period
+    pub fn enqueue(&mut self, proposal: Pubkey, eta: i64, now: i64) -> Result<()> {
+        self.drop_expired(now);
+        let slot = self
+            .queue
+            .iter_mut()
+            .find(|entry| entry.is_empty())
+            .ok_or(ReputationError::TimelockQueueFull)?;
+        *slot = QueuedProposal { proposal, eta };
+        Ok(())
+    }
+
+    /// Take a proposal out of the queue
+    pub fn dequeue(&mut self, proposal: &Pubkey) -> Result<QueuedProposal> {
+        let slot = self
+            .queue
+            .iter_mut()
+            .find(|entry| entry.proposal == *proposal)
+            .ok_or(ReputationError::ProposalNotQueued)?;
+        Ok(std::mem::take(slot))
+    }
+
+    /// Free the slots of proposals that were never executed within
+    /// `PROPOSAL_GRACE_PERIOD` of their ETA
+    pub fn drop_expired(&mut self, now: i64) {
+        for entry in self.queue.iter_mut() {
+            if !entry.is_empty() && entry.is_expired(now) {
+                *entry = QueuedProposal::default();
+            }
+        }
+    }
+}
+
+pub const MAX_QUEUED_PROPOSALS: usize = 16;
+
+/// How long after its ETA a queued proposal can still be executed
+pub const PROPOSAL_GRACE_PERIOD: i64 = 14 * 86400;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct QueuedProposal {
+    pub proposal: Pubkey,
+    pub eta: i64,
+}
+
+impl QueuedProposal {
+    pub const LEN: usize = 32 + 8;
+
+    pub fn is_empty(&self) -> bool {
+        self.proposal == Pubkey::default()
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        now > self.eta.saturating_add(PROPOSAL_GRACE_PERIOD)
+    }
+}
+
+/// A config change a proposal can make
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub enum ConfigChange {
+    DecayRate { decay_rate_per_day: u64 },
+    VouchLockup { vouch_lockup_period: i64 },
+    MinReputationForVouching { min_reputation_for_vouching: u64 },
+    Fees { vouch_fee_bps: u64, dispute_fee_bps: u64, voucher_reward_bps: u64 },
+    MintCaps { mint_epoch_duration: i64, global_mint_cap: u64, agent_mint_cap: u64 },
+    MissedDeadlinePenalty { missed_deadline_penalty: u64 },
+    DecayKeeperReward { decay_keeper_reward: u64 },
+    DecayPolicy { decay_grace_days: i64, reputation_floor: u64, floor_per_task: u64, max_reputation_floor: u64 },
+    Unpause { groups: u8 },
+    Guardian { guardian: Pubkey },
+}
+
+impl ConfigChange {
+    /// Tag plus the largest variants (Guardian, DecayPolicy)
+    pub const LEN: usize = 1 + 32;
+
+    pub fn validate(&self) -> Result<()> {
+        match *self {
+            ConfigChange::DecayRate { decay_rate_per_day } => {
+                require!(decay_rate_per_day <= 10000, ReputationError::InvalidFee);
+            }
+            ConfigChange::Fees { vouch_fee_bps, dispute_fee_bps, voucher_reward_bps } => {
+                require!(vouch_fee_bps <= 10000, ReputationError::InvalidFee);
+                require!(dispute_fee_bps <= 10000, ReputationError::InvalidFee);
+                require!(voucher_reward_bps <= 10000, ReputationError::InvalidFee);
+            }
+            ConfigChange::MintCaps { mint_epoch_duration, .. } => {
+                require!(mint_epoch_duration > 0, ReputationError::InvalidMintCap);
+            }
+            ConfigChange::DecayKeeperReward { decay_keeper_reward } => {
+                require!(decay_keeper_reward <= MAX_DECAY_KEEPER_REWARD, ReputationError::InvalidKeeperReward);
+            }
+            ConfigChange::Unpause { groups } => {
+                require!(groups != 0 && groups & !PAUSE_ALL == 0, ReputationError::InvalidPauseGroups);
+            }
+            ConfigChange::DecayPolicy { decay_grace_days, reputation_floor, max_reputation_floor, .. } => {
+                require!(decay_grace_days >= 0, ReputationError::InvalidDecayPolicy);
+                require!(max_reputation_floor >= reputation_floor, ReputationError::InvalidDecayPolicy);
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    pub fn apply(&self, config: &mut ProtocolConfig, now: i64) -> Result<()> {
+        self.validate()?;
+        match *self {
+            ConfigChange::DecayRate { decay_rate_per_day } => config.decay_rate_per_day = decay_rate_per_day,
+            ConfigChange::VouchLockup { vouch_lockup_period } => config.vouch_lockup_period = vouch_lockup_period,
+            ConfigChange::MinReputationForVouching { min_reputation_for_vouching } => {
+                config.min_reputation_for_vouching = min_reputation_for_vouching;
+            }
+            ConfigChange::Fees { vouch_fee_bps, dispute_fee_bps, voucher_reward_bps } => {
+                config.vouch_fee_bps = vouch_fee_bps;
+                config.dispute_fee_bps = dispute_fee_bps;
+                config.voucher_reward_bps = voucher_reward_bps;
+            }
+            ConfigChange::MintCaps { mint_epoch_duration, global_mint_cap, agent_mint_cap } => {
+                config.update_mint_caps(mint_epoch_duration, global_mint_cap, agent_mint_cap, now)?;
+            }
+            ConfigChange::MissedDeadlinePenalty { missed_deadline_penalty } => {
+                config.missed_deadline_penalty = missed_deadline_penalty;
+            }
+            ConfigChange::DecayKeeperReward { decay_keeper_reward } => {
+                config.decay_keeper_reward = decay_keeper_reward;
+            }
+            ConfigChange::DecayPolicy { decay_grace_days, reputation_floor, floor_per_task, max_reputation_floor } => {
+                config.update_decay_policy(decay_grace_days, reputation_floor, floor_per_task, max_reputation_floor)?;
+            }
+            ConfigChange::Unpause { groups } => config.paused &= !groups,
+            ConfigChange::Guardian { guardian } => config.guardian = guardian,
+        }
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ProposalStatus {
+    Active,
+    Defeated,
+    Queued,
+    Executed,
+    Cancelled,
+    Expired,
+}
+
+/// Governance proposal - PDA seeded by [proposal, id]
+#[account]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Pubkey,
+    pub change: ConfigChange,
+    pub description_hash: [u8; 32], // hash of the off-chain write-up
+    pub created_at: i64,
+    pub voting_ends_at: i64,
+    pub votes_for: u64,
+    pub votes_against: u64,
+    pub status: ProposalStatus,
+    pub eta: i64, // earliest execution time once queued
+    pub bump: u8,
+}
+
+impl Proposal {
+    pub const LEN: usize = 8 + 8 + 32 + ConfigChange::LEN + 32 + 8 + 8 + 8 + 8 + 1 + 8 + 1;
+}
+
+/// One agent's vote on a proposal - PDA seeded by [vote, proposal, voter]
+#[account]
+pub struct VoteRecord {
+    pub proposal: Pubkey,
+    pub voter: Pubkey,
+    pub support: bool,
+    pub weight: u64,
+    pub delegate: Pubkey, // who cast it, if the voter had delegated; default for direct votes
+    pub bump: u8,
+}
+
+impl VoteRecord {
+    pub const LEN: usize = 8 + 32 + 32 + 1 + 8 + 32 + 1;
+}
+
+/// Delegation of an agent's voting power - PDA seeded by [delegation, delegator]
+///
+/// One per delegator. Delegated power only moves one hop: a delegate votes
+/// with its own score plus its delegators' own scores, never theirs' delegators.
+#[account]
+pub struct VoteDelegation {
+    pub delegator: Pubkey,
+    pub delegate: Pubkey,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl VoteDelegation {
+    pub const LEN: usize = 8 + 32 + 32 + 8 + 1;
+}
+
+pub const MAX_TIERS: usize = 8;
+
+/// Requirements for one reputation tier
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct TierThreshold {
+    pub min_score: u64,
+    pub min_positive_vouches: u64,
+}
+
+impl TierThreshold {
+    pub const LEN: usize = 8 + 8;
+}
+
+/// Tier thresholds, lowest first (tier 1 = bronze, 2 = silver, 3 = gold, ...) - PDA seeded by [tier_config]
+#[account]
+#[derive(Default)]
+pub struct TierConfig {
+    pub thresholds: [TierThreshold; MAX_TIERS],
+    pub tier_count: u8,
+    pub bump: u8,
+}
+
+impl TierConfig {
+    pub const LEN: usize = 8 + (TierThreshold::LEN * MAX_TIERS) + 1 + 1;
+
+    /// Highest tier whose thresholds the profile meets, 0 if none
+    pub fn tier_for(&self, profile: &AgentProfile) -> u8 {
+        self.thresholds[..self.tier_count as usize]
+            .iter()
+            .rposition(|t| {
+                profile.reputation_score >= t.min_score && profile.positive_vouches >= t.min_positive_vouches
+            })
+            .map_or(0, |i| i as u8 + 1)
+    }
+}
+
+/// One entry of the `get_reputation` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DomainScore {
+    pub category_id: u32,
+    pub score: u64,
+}
+
+/// Return value of `get_reputation`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ReputationView {
+    pub score: u64,
+    pub domains: Vec<DomainScore>,
+}
+
+/// Reputation snapshot - PDA seeded by [snapshot, epoch]
+///
+/// Stores a merkle root over (agent, effective score) leaves so that other
+/// programs and airdrops can check "score at epoch N" against a fixed value.
+#[account]
+pub struct ReputationSnapshot {
+    pub epoch: u64,
+    pub merkle_root: [u8; 32],
+    pub agent_count: u64,
+    pub published_by: Pubkey,
+    pub created_at: i64,
+    pub bump: u8,
+}
+
+impl ReputationSnapshot {
+    pub const LEN: usize = 8 + 8 + 32 + 8 + 32 + 8 + 1;
 }
 
 /// Reputation calculation helpers
@@ -79,12 +1112,608 @@ pub fn calculate_decay(current_reputation: u64, days_inactive: i64, decay_rate:
     remaining
 }
 
+/// Days of decay owed for inactivity since `last_activity`, once the grace
+/// period is used up
+pub fn calculate_decay_days(last_activity: i64, now: i64, grace_days: i64) -> i64 {
+    (now.saturating_sub(last_activity) / 86400).saturating_sub(grace_days).max(0)
+}
+
+/// Lowest score decay can take an agent to: the base floor plus a bonus per
+/// lifetime task, capped at `max_reputation_floor`
+pub fn calculate_reputation_floor(config: &ProtocolConfig, tasks_completed: u64) -> u64 {
+    config
+        .reputation_floor
+        .saturating_add(config.floor_per_task.saturating_mul(tasks_completed))
+        .min(config.max_reputation_floor)
+}
+
+/// Decay an agent's score by `days`, stopping at its floor. Scores already
+/// below the floor are left where they are.
+pub fn calculate_profile_decay(score: u64, days: i64, tasks_completed: u64, config: &ProtocolConfig) -> u64 {
+    let floor = calculate_reputation_floor(config, tasks_completed).min(score);
+    calculate_decay(score, days, config.decay_rate_per_day).max(floor)
+}
+
 pub fn calculate_vouch_bonus(positive_vouches: u64, negative_vouches: u64) -> i64 {
     let total = positive_vouches.saturating_add(negative_vouches);
     if total == 0 {
         return 0;
     }
-    let ratio = (positive_vouches as i64 * 100) / (total as i64);
+    let ratio = ((positive_vouches as i128 * 100) / (total as i128)) as i64;
     // Bonus ranges from -50 to +50 based on vouch ratio
     (ratio - 50) / 2
 }
+
+/// Fixed-point scale for `AgentProfile.reward_per_share`
+pub const REWARD_PRECISION: u128 = 1_000_000_000_000;
+
+/// Rewards accrued by `stake` at the given accumulator value
+pub fn calculate_accrued_reward(stake: u64, reward_per_share: u128) -> u128 {
+    (stake as u128).saturating_mul(reward_per_share) / REWARD_PRECISION
+}
+
+/// Rewards a vouch can claim right now
+pub fn calculate_pending_reward(stake: u64, reward_per_share: u128, reward_debt: u128) -> u64 {
+    calculate_accrued_reward(stake, reward_per_share)
+        .saturating_sub(reward_debt)
+        .min(u64::MAX as u128) as u64
+}
+
+/// Fee owed on `amount` at `fee_bps` basis points, rounded down
+pub fn calculate_fee(amount: u64, fee_bps: u64) -> u64 {
+    ((amount as u128) * (fee_bps as u128) / 10000) as u64
+}
+
+/// Influence of a vouch in basis points: ramps linearly from 0 at creation
+/// to 10000 once the lockup period has passed
+pub fn calculate_vouch_weight(created_at: i64, now: i64, lockup_period: i64) -> u64 {
+    if lockup_period <= 0 {
+        return 10000;
+    }
+    let age = now.saturating_sub(created_at).clamp(0, lockup_period);
+    ((age as i128) * 10000 / (lockup_period as i128)) as u64
+}
+
+/// Score an agent effectively holds right now: pending decay is applied and
+/// the result is scaled by the vouch bonus, with young vouches only partly
+/// counted. Used for snapshots, governance weight and other "point in time"
+/// views that must not depend on when decay was last cranked.
+pub fn calculate_effective_score(profile: &AgentProfile, now: i64, config: &ProtocolConfig) -> u64 {
+    let days = calculate_decay_days(profile.last_activity_timestamp, now, config.decay_grace_days);
+    let decayed = calculate_profile_decay(profile.reputation_score, days, profile.total_tasks_completed, config);
+
+    let (positive, negative) = profile.weighted_vouches(now, config.vouch_lockup_period);
+    let bonus = calculate_vouch_bonus(positive, negative);
+    let scaled = (decayed as i128) * (100 + bonus as i128) / 100;
+    scaled.clamp(0, u64::MAX as i128) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::jury::{draw_jurors, seed_slot_hash};
+    use crate::receipt::{is_ed25519_verification, receipt_message};
+    use anchor_lang::solana_program::ed25519_program;
+    use anchor_lang::solana_program::instruction::Instruction;
+
+    const DAY: i64 = 86400;
+
+    fn vouch(amount: u64, now: i64) -> VouchRecord {
+        let mut vouch = VouchRecord {
+            voucher: Pubkey::new_unique(),
+            vouched_for: Pubkey::new_unique(),
+            amount,
+            is_positive: true,
+            is_escrowed: true,
+            created_at: now,
+            ..Default::default()
+        };
+        vouch.init_tranches(amount, now);
+        vouch
+    }
+
+    fn config() -> ProtocolConfig {
+        ProtocolConfig {
+            decay_rate_per_day: 1000,
+            decay_grace_days: 3,
+            reputation_floor: 100,
+            floor_per_task: 10,
+            max_reputation_floor: 150,
+            ..Default::default()
+        }
+    }
+
+    fn profile() -> AgentProfile {
+        AgentProfile {
+            owner: Pubkey::new_unique(),
+            is_active: true,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn top_up_only_relocks_the_new_tranche() {
+        let mut vouch = vouch(100, 0);
+        assert_eq!(vouch.unlocked_amount(DAY - 1, DAY), 0);
+        assert!(vouch.is_fully_unlocked(DAY, DAY));
+
+        vouch.add_tranche(50, DAY);
+        assert_eq!(vouch.amount, 150);
+        assert_eq!(vouch.unlocked_amount(DAY, DAY), 100);
+        assert!(!vouch.is_fully_unlocked(2 * DAY - 1, DAY));
+        assert!(vouch.is_fully_unlocked(2 * DAY, DAY));
+    }
+
+    #[test]
+    fn full_tranche_slots_fold_into_the_newest() {
+        let mut vouch = vouch(10, 0);
+        for i in 1..=MAX_VOUCH_TRANCHES as i64 {
+            vouch.add_tranche(10, i * DAY);
+        }
+        assert_eq!(vouch.tranche_count as usize, MAX_VOUCH_TRANCHES);
+        assert_eq!(vouch.amount, 10 * (MAX_VOUCH_TRANCHES as u64 + 1));
+
+        let last = vouch.tranches[MAX_VOUCH_TRANCHES - 1];
+        assert_eq!(last.amount, 20);
+        assert_eq!(last.locked_at, MAX_VOUCH_TRANCHES as i64 * DAY);
+    }
+
+    #[test]
+    fn remove_unlocked_takes_oldest_first_and_drops_empty_tranches() {
+        let mut vouch = vouch(100, 0);
+        vouch.add_tranche(50, DAY);
+        vouch.add_tranche(25, 2 * DAY);
+
+        // At 2 days only the first two tranches are unlocked
+        assert_eq!(vouch.unlocked_amount(2 * DAY, DAY), 150);
+        vouch.remove_unlocked(120, 2 * DAY, DAY);
+
+        assert_eq!(vouch.amount, 55);
+        assert_eq!(vouch.tranche_count, 2);
+        assert_eq!(vouch.tranches[0].amount, 30);
+        assert_eq!(vouch.tranches[0].locked_at, DAY);
+        assert_eq!(vouch.tranches[1].amount, 25);
+        assert_eq!(vouch.unlocked_amount(2 * DAY, DAY), 30);
+    }
+
+    #[test]
+    fn zero_lockup_unlocks_immediately() {
+        let mut vouch = vouch(100, 0);
+        vouch.add_tranche(50, 0);
+        assert!(vouch.is_fully_unlocked(0, 0));
+        vouch.remove_unlocked(150, 0, 0);
+        assert_eq!(vouch.amount, 0);
+        assert_eq!(vouch.tranche_count, 0);
+    }
+
+    #[test]
+    fn vouch_weight_ramps_linearly() {
+        assert_eq!(calculate_vouch_weight(0, 0, 10 * DAY), 0);
+        assert_eq!(calculate_vouch_weight(0, 5 * DAY, 10 * DAY), 5000);
+        assert_eq!(calculate_vouch_weight(0, 10 * DAY, 10 * DAY), 10000);
+        assert_eq!(calculate_vouch_weight(0, 20 * DAY, 10 * DAY), 10000);
+        // A clock behind the vouch never goes negative
+        assert_eq!(calculate_vouch_weight(DAY, 0, 10 * DAY), 0);
+    }
+
+    #[test]
+    fn zero_duration_ramp_is_full_weight() {
+        assert_eq!(calculate_vouch_weight(DAY, 0, 0), 10000);
+        assert_eq!(calculate_vouch_weight(DAY, DAY, 0), 10000);
+
+        let mut profile = profile();
+        profile.positive_vouches = 1;
+        profile.push_vouch_ramp(true, 0, 0);
+        assert_eq!(profile.weighted_vouches(0, 0), (10000, 0));
+    }
+
+    #[test]
+    fn weighted_vouches_discount_maturing_ramps() {
+        let mut profile = profile();
+        profile.positive_vouches = 3;
+        profile.negative_vouches = 1;
+        profile.push_vouch_ramp(true, 0, 10 * DAY);
+        profile.push_vouch_ramp(false, 5 * DAY, 10 * DAY);
+
+        // One positive at half weight, the negative at zero, two matured positives
+        assert_eq!(profile.weighted_vouches(5 * DAY, 10 * DAY), (25000, 0));
+        assert_eq!(profile.weighted_vouches(15 * DAY, 10 * DAY), (30000, 10000));
+    }
+
+    #[test]
+    fn full_ramp_slots_fold_into_the_newest() {
+        let mut profile = profile();
+        for i in 0..MAX_VOUCH_RAMPS as i64 + 1 {
+            profile.push_vouch_ramp(true, i, 10 * DAY);
+        }
+        let newest = profile.vouch_ramps.iter().max_by_key(|r| r.started_at).unwrap();
+        assert_eq!(newest.started_at, MAX_VOUCH_RAMPS as i64);
+        assert_eq!(newest.positive, 2);
+
+        // Removing a vouch finds the ramp it was folded into
+        profile.remove_vouch_ramp(true, MAX_VOUCH_RAMPS as i64 - 1, MAX_VOUCH_RAMPS as i64, 10 * DAY);
+        let total: u32 = profile.vouch_ramps.iter().map(|r| r.positive).sum();
+        assert_eq!(total, MAX_VOUCH_RAMPS as u32);
+    }
+
+    #[test]
+    fn removing_a_matured_vouch_leaves_younger_ramps_alone() {
+        let mut profile = profile();
+        // A vouches at 0 and matures; B vouches after, which clears A's ramp
+        profile.positive_vouches = 1;
+        profile.push_vouch_ramp(true, 0, DAY);
+        profile.positive_vouches = 2;
+        profile.push_vouch_ramp(true, 2 * DAY, DAY);
+        assert_eq!(profile.weighted_vouches(2 * DAY, DAY), (10000, 0));
+
+        // A withdraws; B must still be ramping
+        profile.remove_vouch(&vouch(100, 0), 2 * DAY, DAY);
+        assert_eq!(profile.positive_vouches, 1);
+        assert_eq!(profile.weighted_vouches(2 * DAY, DAY), (0, 0));
+        assert_eq!(profile.weighted_vouches(2 * DAY + DAY / 2, DAY), (5000, 0));
+    }
+
+    #[test]
+    fn matured_ramps_are_recycled() {
+        let mut profile = profile();
+        for i in 0..MAX_VOUCH_RAMPS as i64 {
+            profile.push_vouch_ramp(true, i, DAY);
+        }
+        profile.push_vouch_ramp(false, 2 * DAY, DAY);
+        assert_eq!(profile.vouch_ramps.iter().filter(|r| !r.is_empty()).count(), 1);
+    }
+
+    #[test]
+    fn reputation_at_before_any_checkpoint_is_zero() {
+        let mut profile = profile();
+        assert_eq!(profile.reputation_at(0), Some(0));
+
+        profile.set_reputation_score(100, 10);
+        assert_eq!(profile.reputation_at(9), Some(0));
+        assert_eq!(profile.reputation_at(10), Some(100));
+        assert_eq!(profile.reputation_at(i64::MAX), Some(100));
+    }
+
+    #[test]
+    fn same_second_changes_share_a_checkpoint() {
+        let mut profile = profile();
+        profile.set_reputation_score(100, 10);
+        profile.set_reputation_score(150, 10);
+        profile.set_reputation_score(200, 20);
+
+        assert_eq!(profile.checkpoint_count, 2);
+        assert_eq!(profile.reputation_at(15), Some(150));
+        assert_eq!(profile.reputation_at(20), Some(200));
+    }
+
+    #[test]
+    fn wrapped_history_forgets_the_oldest_checkpoints() {
+        let mut profile = profile();
+        let total = MAX_SCORE_CHECKPOINTS as i64 + 5;
+        for i in 1..=total {
+            profile.set_reputation_score(i as u64 * 10, i * 100);
+        }
+        assert_eq!(profile.checkpoint_count as usize, MAX_SCORE_CHECKPOINTS);
+        assert_eq!(profile.checkpoint_head, 5);
+
+        // The first five were overwritten, so anything before the sixth is gone
+        assert_eq!(profile.reputation_at(0), None);
+        assert_eq!(profile.reputation_at(599), None);
+        assert_eq!(profile.reputation_at(600), Some(60));
+        assert_eq!(profile.reputation_at(650), Some(60));
+        // Checkpoints on both sides of the wrap point
+        assert_eq!(profile.reputation_at(MAX_SCORE_CHECKPOINTS as i64 * 100), Some(MAX_SCORE_CHECKPOINTS as u64 * 10));
+        assert_eq!(profile.reputation_at(total * 100 - 1), Some((total as u64 - 1) * 10));
+        assert_eq!(profile.reputation_at(total * 100), Some(total as u64 * 10));
+    }
+
+    #[test]
+    fn decay_starts_after_the_grace_period() {
+        assert_eq!(calculate_decay_days(0, 3 * DAY, 3), 0);
+        assert_eq!(calculate_decay_days(0, 4 * DAY - 1, 3), 0);
+        assert_eq!(calculate_decay_days(0, 4 * DAY, 3), 1);
+        assert_eq!(calculate_decay_days(0, 10 * DAY, 0), 10);
+        // Activity stamped after `now` owes nothing
+        assert_eq!(calculate_decay_days(DAY, 0, 0), 0);
+    }
+
+    #[test]
+    fn floor_scales_with_tasks_up_to_the_cap() {
+        let config = config();
+        assert_eq!(calculate_reputation_floor(&config, 0), 100);
+        assert_eq!(calculate_reputation_floor(&config, 3), 130);
+        assert_eq!(calculate_reputation_floor(&config, 10), 150);
+        assert_eq!(calculate_reputation_floor(&config, u64::MAX), 150);
+
+        assert_eq!(calculate_profile_decay(1000, 1, 3, &config), 900);
+        assert_eq!(calculate_profile_decay(1000, 365, 3, &config), 130);
+    }
+
+    #[test]
+    fn floor_above_the_score_leaves_it_alone() {
+        let config = config();
+        assert_eq!(calculate_profile_decay(50, 365, 0, &config), 50);
+        assert_eq!(calculate_profile_decay(120, 365, 10, &config), 120);
+    }
+
+    #[test]
+    fn domain_decay_uses_the_grace_period_and_floor() {
+        let config = config();
+        let domain = DomainReputation {
+            agent: Pubkey::new_unique(),
+            category_id: 0,
+            score: 1000,
+            tasks_completed: 2,
+            last_activity_timestamp: 0,
+            bump: 0,
+        };
+        assert_eq!(domain.pending_decay(4 * DAY - 1, &config), (0, 1000));
+        assert_eq!(domain.pending_decay(4 * DAY, &config), (1, 900));
+        assert_eq!(domain.pending_decay(400 * DAY, &config), (397, 120));
+    }
+
+    /// Raw SlotHashes data for `slots`, newest first like the sysvar. Each
+    /// slot's hash is its low byte repeated.
+    fn slot_hashes(slots: &[u64]) -> Vec<u8> {
+        let mut data = (slots.len() as u64).to_le_bytes().to_vec();
+        for &slot in slots {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(&[slot as u8; 32]);
+        }
+        data
+    }
+
+    #[test]
+    fn seed_slot_hash_reads_the_seed_slot() {
+        let data = slot_hashes(&[12, 11, 10, 9]);
+        assert_eq!(seed_slot_hash(&data, 10).unwrap(), &[10; 32]);
+        assert_eq!(seed_slot_hash(&data, 12).unwrap(), &[12; 32]);
+    }
+
+    #[test]
+    fn seed_slot_hash_steps_over_a_skipped_slot() {
+        let data = slot_hashes(&[14, 13, 11, 10]);
+        assert_eq!(seed_slot_hash(&data, 12).unwrap(), &[13; 32]);
+    }
+
+    #[test]
+    fn seed_slot_hash_waits_for_the_seed_slot() {
+        let data = slot_hashes(&[9, 8]);
+        assert_eq!(seed_slot_hash(&data, 10).unwrap_err(), ReputationError::SeedSlotNotReached.into());
+        assert_eq!(seed_slot_hash(&[], 10).unwrap_err(), ReputationError::SeedSlotNotReached.into());
+    }
+
+    #[test]
+    fn seed_slot_hash_fails_once_the_slot_has_left_the_window() {
+        // Without an entry at or before slot 10, a skip can't be told from an eviction
+        let data = slot_hashes(&[13, 12, 11]);
+        assert_eq!(seed_slot_hash(&data, 10).unwrap_err(), ReputationError::SeedSlotExpired.into());
+    }
+
+    #[test]
+    fn draw_is_a_deterministic_shuffle_of_the_pool() {
+        let pool: Vec<Pubkey> = (0..10).map(|_| Pubkey::new_unique()).collect();
+        let order = draw_jurors(&pool, &[1; 32]);
+        assert_eq!(order, draw_jurors(&pool, &[1; 32]));
+        assert_ne!(order, draw_jurors(&pool, &[2; 32]));
+
+        let mut sorted = order.clone();
+        sorted.sort();
+        let mut expected = pool.clone();
+        expected.sort();
+        assert_eq!(sorted, expected);
+
+        assert!(draw_jurors(&[], &[1; 32]).is_empty());
+    }
+
+    #[test]
+    fn appeals_grow_the_jury_and_double_the_bond() {
+        let jury_config = JuryConfig { jury_size: 3, appeal_bond: 100, ..Default::default() };
+        assert_eq!(jury_config.jury_size_for_round(1), 3);
+        assert_eq!(jury_config.jury_size_for_round(2), 7);
+        assert_eq!(jury_config.jury_size_for_round(3), 15);
+
+        assert_eq!(jury_config.appeal_bond_for(0), 100);
+        assert_eq!(jury_config.appeal_bond_for(1), 200);
+        assert_eq!(jury_config.appeal_bond_for(3), 800);
+        assert_eq!(jury_config.appeal_bond_for(200), u64::MAX);
+    }
+
+    /// Ed25519 precompile instruction checking one signature by `signer` over
+    /// `message`, with everything stored in its own data
+    fn ed25519_ix(signer: &Pubkey, message: &[u8], message_ix: u16) -> Instruction {
+        let pubkey_offset: u16 = 2 + 14;
+        let signature_offset = pubkey_offset + 32;
+        let message_offset = signature_offset + 64;
+
+        let mut data = vec![1, 0];
+        for value in [
+            signature_offset,
+            u16::MAX,
+            pubkey_offset,
+            u16::MAX,
+            message_offset,
+            message.len() as u16,
+            message_ix,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(signer.as_ref());
+        data.extend_from_slice(&[0; 64]);
+        data.extend_from_slice(message);
+
+        Instruction { program_id: ed25519_program::ID, accounts: vec![], data }
+    }
+
+    #[test]
+    fn receipt_message_binds_every_field() {
+        let agent = Pubkey::new_unique();
+        let message = receipt_message("task", &agent, 10, 1, 100, 7);
+        assert!(message.starts_with(b"agentreputation:receipt:v1"));
+        assert!(message.ends_with(b"task"));
+
+        assert_ne!(message, receipt_message("task2", &agent, 10, 1, 100, 7));
+        assert_ne!(message, receipt_message("task", &Pubkey::new_unique(), 10, 1, 100, 7));
+        assert_ne!(message, receipt_message("task", &agent, 11, 1, 100, 7));
+        assert_ne!(message, receipt_message("task", &agent, 10, 2, 100, 7));
+        assert_ne!(message, receipt_message("task", &agent, 10, 1, 101, 7));
+        assert_ne!(message, receipt_message("task", &agent, 10, 1, 100, 8));
+    }
+
+    #[test]
+    fn ed25519_verification_must_match_signer_and_message() {
+        let client = Pubkey::new_unique();
+        let message = receipt_message("task", &Pubkey::new_unique(), 10, 1, 100, 7);
+        let ix = ed25519_ix(&client, &message, u16::MAX);
+
+        assert!(is_ed25519_verification(&ix, &client, &message));
+        assert!(!is_ed25519_verification(&ix, &Pubkey::new_unique(), &message));
+        assert!(!is_ed25519_verification(&ix, &client, &message[1..]));
+    }
+
+    #[test]
+    fn ed25519_verification_rejects_foreign_data() {
+        let client = Pubkey::new_unique();
+        let message = b"receipt".to_vec();
+
+        // Message read from another instruction than the one we inspect
+        let ix = ed25519_ix(&client, &message, 0);
+        assert!(!is_ed25519_verification(&ix, &client, &message));
+
+        let mut ix = ed25519_ix(&client, &message, u16::MAX);
+        ix.program_id = Pubkey::new_unique();
+        assert!(!is_ed25519_verification(&ix, &client, &message));
+
+        // More than one signature
+        let mut ix = ed25519_ix(&client, &message, u16::MAX);
+        ix.data[0] = 2;
+        assert!(!is_ed25519_verification(&ix, &client, &message));
+
+        let mut ix = ed25519_ix(&client, &message, u16::MAX);
+        ix.data.truncate(10);
+        assert!(!is_ed25519_verification(&ix, &client, &message));
+    }
+
+    #[test]
+    fn mint_caps_apply_per_epoch() {
+        let mut config = ProtocolConfig {
+            mint_epoch_duration: DAY,
+            global_mint_cap: 100,
+            agent_mint_cap: 60,
+            ..Default::default()
+        };
+        let (mut alice, mut bob, mut carol) = (profile(), profile(), profile());
+
+        config.record_mint(&mut alice, 60, 0).unwrap();
+        assert_eq!(
+            config.record_mint(&mut alice, 1, 0).unwrap_err(),
+            ReputationError::AgentMintCapExceeded.into()
+        );
+        config.record_mint(&mut bob, 40, 0).unwrap();
+        assert_eq!(
+            config.record_mint(&mut carol, 1, DAY - 1).unwrap_err(),
+            ReputationError::GlobalMintCapExceeded.into()
+        );
+        // Failed mints aren't counted
+        assert_eq!((config.epoch_minted, alice.epoch_minted, carol.epoch_minted), (100, 60, 0));
+
+        // Both counters start over in the next epoch
+        config.record_mint(&mut alice, 60, DAY).unwrap();
+        assert_eq!((config.mint_epoch, config.epoch_minted), (1, 60));
+        assert_eq!((alice.mint_epoch, alice.epoch_minted), (1, 60));
+        // Bob's counter is stale until his next mint resets it
+        config.record_mint(&mut bob, 40, DAY).unwrap();
+        assert_eq!((bob.mint_epoch, bob.epoch_minted), (1, 40));
+    }
+
+    #[test]
+    fn zero_mint_caps_are_uncapped() {
+        let mut config = ProtocolConfig { mint_epoch_duration: DAY, ..Default::default() };
+        let mut agent = profile();
+        config.record_mint(&mut agent, u64::MAX, 0).unwrap();
+        assert_eq!(
+            config.record_mint(&mut agent, 1, 0).unwrap_err(),
+            ReputationError::MathOverflow.into()
+        );
+    }
+
+    #[test]
+    fn pause_checks_any_of_the_groups() {
+        let mut config = config();
+        config.require_not_paused(PAUSE_ALL).unwrap();
+
+        config.paused = PAUSE_WITHDRAWALS;
+        config.require_not_paused(PAUSE_MINTING).unwrap();
+        config.require_not_paused(PAUSE_VOUCHING | PAUSE_GOVERNANCE).unwrap();
+        assert_eq!(
+            config.require_not_paused(PAUSE_WITHDRAWALS).unwrap_err(),
+            ReputationError::ProtocolPaused.into()
+        );
+        assert_eq!(
+            config.require_not_paused(PAUSE_GOVERNANCE | PAUSE_WITHDRAWALS).unwrap_err(),
+            ReputationError::ProtocolPaused.into()
+        );
+    }
+
+    #[test]
+    fn timelock_queue_fills_and_frees_slots() {
+        let mut timelock = Timelock::default();
+        let proposals: Vec<Pubkey> = (0..MAX_QUEUED_PROPOSALS).map(|_| Pubkey::new_unique()).collect();
+        for (i, &proposal) in proposals.iter().enumerate() {
+            timelock.enqueue(proposal, i as i64, 0).unwrap();
+        }
+        assert_eq!(
+            timelock.enqueue(Pubkey::new_unique(), 0, 0).unwrap_err(),
+            ReputationError::TimelockQueueFull.into()
+        );
+
+        let entry = timelock.dequeue(&proposals[3]).unwrap();
+        assert_eq!((entry.proposal, entry.eta), (proposals[3], 3));
+        assert_eq!(
+            timelock.dequeue(&proposals[3]).err(),
+            Some(ReputationError::ProposalNotQueued.into())
+        );
+        timelock.enqueue(Pubkey::new_unique(), 0, 0).unwrap();
+    }
+
+    #[test]
+    fn enqueue_drops_proposals_past_their_grace_period() {
+        let mut timelock = Timelock::default();
+        let stale = Pubkey::new_unique();
+        timelock.enqueue(stale, 0, 0).unwrap();
+        for _ in 1..MAX_QUEUED_PROPOSALS {
+            timelock.enqueue(Pubkey::new_unique(), DAY, 0).unwrap();
+        }
+
+        // The last moment it can still run, it holds its slot
+        assert_eq!(
+            timelock.enqueue(Pubkey::new_unique(), DAY, PROPOSAL_GRACE_PERIOD).unwrap_err(),
+            ReputationError::TimelockQueueFull.into()
+        );
+        timelock.enqueue(Pubkey::new_unique(), DAY, PROPOSAL_GRACE_PERIOD + 1).unwrap();
+        assert_eq!(timelock.dequeue(&stale).err(), Some(ReputationError::ProposalNotQueued.into()));
+    }
+
+    #[test]
+    fn tier_needs_both_score_and_vouches() {
+        let mut tier_config = TierConfig { tier_count: 3, ..Default::default() };
+        tier_config.thresholds[..3].copy_from_slice(&[
+            TierThreshold { min_score: 100, min_positive_vouches: 0 },
+            TierThreshold { min_score: 500, min_positive_vouches: 2 },
+            TierThreshold { min_score: 1000, min_positive_vouches: 5 },
+        ]);
+        let tier = |reputation_score, positive_vouches| {
+            tier_config.tier_for(&AgentProfile { reputation_score, positive_vouches, ..profile() })
+        };
+
+        assert_eq!(tier(99, 10), 0);
+        assert_eq!(tier(100, 0), 1);
+        assert_eq!(tier(600, 1), 1);
+        assert_eq!(tier(600, 2), 2);
+        assert_eq!(tier(2000, 3), 2);
+        assert_eq!(tier(2000, 5), 3);
+
+        // No tiers configured
+        assert_eq!(TierConfig::default().tier_for(&AgentProfile { reputation_score: 2000, ..profile() }), 0);
+    }
+}
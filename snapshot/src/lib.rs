@@ -0,0 +1,196 @@
+//! Off-chain builder for `ReputationSnapshot` merkle trees.
+//!
+//! Feed it the `AgentProfile` accounts from an indexer (or `getProgramAccounts`),
+//! publish `root()` with `publish_snapshot`, and hand each agent its `proof()` so
+//! it can be checked on-chain with `verify_snapshot_proof`.
+
+use agentreputation_dao::merkle::{hash_pair, snapshot_leaf, verify_proof};
+use agentreputation_dao::state::{calculate_effective_score, AgentProfile, ProtocolConfig};
+use anchor_lang::prelude::Pubkey;
+
+/// One leaf of the snapshot tree
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SnapshotEntry {
+    pub agent: Pubkey,
+    pub score: u64,
+}
+
+/// Inclusion proof for a single agent, ready to pass to `verify_snapshot_proof`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SnapshotProof {
+    pub agent: Pubkey,
+    pub score: u64,
+    pub proof: Vec<[u8; 32]>,
+}
+
+#[derive(Clone, Debug)]
+pub struct SnapshotTree {
+    entries: Vec<SnapshotEntry>,
+    // layers[0] are the leaves, the last layer holds only the root
+    layers: Vec<Vec<[u8; 32]>>,
+}
+
+impl SnapshotTree {
+    /// Build a tree from raw entries. Entries are sorted by agent so the same
+    /// input always yields the same root; duplicate agents keep the first score.
+    pub fn from_entries(mut entries: Vec<SnapshotEntry>) -> Self {
+        entries.sort_by_key(|e| e.agent);
+        entries.dedup_by(|a, b| a.agent == b.agent);
+
+        let leaves: Vec<[u8; 32]> = entries
+            .iter()
+            .map(|e| snapshot_leaf(&e.agent, e.score))
+            .collect();
+
+        let mut layers = vec![leaves];
+        while layers.last().is_some_and(|layer| layer.len() > 1) {
+            let next = layers
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(a, b),
+                    // Odd node out is carried up unchanged
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+
+        Self { entries, layers }
+    }
+
+    /// Build a tree from indexed profiles, scoring each one with the same
+    /// effective-score logic the program uses at `now`
+    pub fn from_profiles<'a, I>(profiles: I, config: &ProtocolConfig, now: i64) -> Self
+    where
+        I: IntoIterator<Item = &'a AgentProfile>,
+    {
+        let entries = profiles
+            .into_iter()
+            .map(|profile| SnapshotEntry {
+                agent: profile.owner,
+                score: calculate_effective_score(profile, now, config),
+            })
+            .collect();
+        Self::from_entries(entries)
+    }
+
+    /// Merkle root to publish. An empty tree has an all-zero root.
+    pub fn root(&self) -> [u8; 32] {
+        self.layers
+            .last()
+            .and_then(|layer| layer.first())
+            .copied()
+            .unwrap_or([0; 32])
+    }
+
+    pub fn entries(&self) -> &[SnapshotEntry] {
+        &self.entries
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Inclusion proof for `agent`, or `None` if it isn't in the snapshot
+    pub fn proof(&self, agent: &Pubkey) -> Option<SnapshotProof> {
+        let mut index = self
+            .entries
+            .binary_search_by(|e| e.agent.cmp(agent))
+            .ok()?;
+        let entry = self.entries[index];
+
+        let mut proof = Vec::new();
+        for layer in &self.layers[..self.layers.len() - 1] {
+            let sibling = index ^ 1;
+            if let Some(node) = layer.get(sibling) {
+                proof.push(*node);
+            }
+            index /= 2;
+        }
+
+        Some(SnapshotProof {
+            agent: entry.agent,
+            score: entry.score,
+            proof,
+        })
+    }
+
+    /// Check a proof against this tree's root, mirroring the on-chain check
+    pub fn verify(&self, proof: &SnapshotProof) -> bool {
+        verify_proof(&proof.proof, &self.root(), snapshot_leaf(&proof.agent, proof.score))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entries(n: usize) -> Vec<SnapshotEntry> {
+        (0..n)
+            .map(|i| SnapshotEntry {
+                agent: Pubkey::new_unique(),
+                score: 100 + i as u64,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn empty_tree_has_zero_root_and_no_proofs() {
+        let tree = SnapshotTree::from_entries(Vec::new());
+        assert!(tree.is_empty());
+        assert_eq!(tree.root(), [0; 32]);
+        assert!(tree.proof(&Pubkey::new_unique()).is_none());
+    }
+
+    #[test]
+    fn single_entry_root_is_its_leaf() {
+        let entry = entries(1)[0];
+        let tree = SnapshotTree::from_entries(vec![entry]);
+        assert_eq!(tree.root(), snapshot_leaf(&entry.agent, entry.score));
+
+        let proof = tree.proof(&entry.agent).unwrap();
+        assert!(proof.proof.is_empty());
+        assert!(tree.verify(&proof));
+    }
+
+    #[test]
+    fn every_proof_verifies_for_odd_and_even_sizes() {
+        for n in [2, 3, 5, 8, 13] {
+            let tree = SnapshotTree::from_entries(entries(n));
+            for entry in tree.entries() {
+                let proof = tree.proof(&entry.agent).unwrap();
+                assert!(tree.verify(&proof), "proof failed for {n} entries");
+            }
+        }
+    }
+
+    #[test]
+    fn tampered_score_fails_verification() {
+        let tree = SnapshotTree::from_entries(entries(4));
+        let mut proof = tree.proof(&tree.entries()[2].agent).unwrap();
+        proof.score += 1;
+        assert!(!tree.verify(&proof));
+    }
+
+    #[test]
+    fn root_ignores_input_order_and_duplicates() {
+        let mut input = entries(6);
+        let root = SnapshotTree::from_entries(input.clone()).root();
+
+        input.reverse();
+        let mut duplicate = input[0];
+        duplicate.score = 0;
+        input.push(duplicate);
+
+        let tree = SnapshotTree::from_entries(input);
+        assert_eq!(tree.len(), 6);
+        assert_eq!(tree.root(), root);
+    }
+}